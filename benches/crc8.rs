@@ -0,0 +1,42 @@
+//! Compares `crc8::calculate` with `crc8::calculate_branchless`.
+//!
+//! Run with `cargo bench --bench crc8`. The timings are taken on the host, so they only hint at
+//! the relative cost on a microcontroller.
+
+use sensirion_i2c::crc8;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 100_000;
+
+/// Keep the optimizer from discarding a value (`std::hint::black_box` needs Rust 1.66).
+fn black_box<T>(value: T) -> T {
+    let result = unsafe { core::ptr::read_volatile(&value) };
+    core::mem::forget(value);
+    result
+}
+
+fn bench(name: &str, data: &[u8], f: fn(&[u8]) -> u8) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f(black_box(data)));
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:<22} {:>8.1} ns/iter",
+        name,
+        elapsed.as_nanos() as f64 / f64::from(ITERATIONS)
+    );
+    elapsed
+}
+
+fn main() {
+    let mut data = [0; 64];
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte = (i as u8).wrapping_mul(37);
+    }
+    assert_eq!(crc8::calculate(&data), crc8::calculate_branchless(&data));
+
+    println!("crc8 over {} bytes, {} iterations", data.len(), ITERATIONS);
+    bench("calculate", &data, crc8::calculate);
+    bench("calculate_branchless", &data, crc8::calculate_branchless);
+}
@@ -2,28 +2,135 @@
 
 /// Errors which can happen in the crc8 module
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Error {
     /// CRC validation failed
     CrcError,
+    /// The provided buffer has an invalid size
+    InvalidBufferSize,
 }
 
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::CrcError => f.write_str("CRC validation failed"),
+            Error::InvalidBufferSize => f.write_str("invalid buffer size"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Generator polynomial of the Sensirion CRC8 (x⁸ + x⁵ + x⁴ + 1), without the implicit x⁸ term.
+pub const POLYNOMIAL: u8 = 0x31;
+
+/// Initial value of the Sensirion CRC8 calculation.
+///
+/// The input and output are not reflected and there is no final XOR, so together with
+/// [`POLYNOMIAL`] this is the catalogued CRC-8/NRSC-5.
+pub const INIT: u8 = 0xff;
+
 /// Calculate the CRC8 checksum.
 pub fn calculate(data: &[u8]) -> u8 {
-    const CRC8_POLYNOMIAL: u8 = 0x31;
-    let mut crc: u8 = 0xff;
+    let mut crc = Crc8::new();
+    crc.update(data);
+    crc.finish()
+}
+
+/// Streaming CRC8 calculation.
+///
+/// The checksum can be fed in several steps, e.g. across the poll cycles of a driver state
+/// machine. The whole state is a single byte, so it can be stored with [`Crc8::state`] and
+/// restored with [`Crc8::from_state`].
+///
+/// ```
+/// use sensirion_i2c::crc8::{self, Crc8};
+///
+/// let mut crc = Crc8::new();
+/// crc.update(&[0xbe]);
+/// crc.update(&[0xef]);
+/// assert_eq!(crc.finish(), crc8::calculate(&[0xbe, 0xef]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc8 {
+    crc: u8,
+}
+
+impl Crc8 {
+    /// Start a new CRC8 calculation.
+    pub const fn new() -> Self {
+        Crc8 { crc: INIT }
+    }
+
+    /// Resume a CRC8 calculation from a state previously returned by [`Crc8::state`].
+    pub const fn from_state(state: u8) -> Self {
+        Crc8 { crc: state }
+    }
+
+    /// Return the current state of the calculation.
+    pub const fn state(&self) -> u8 {
+        self.crc
+    }
+
+    /// Feed more data into the calculation.
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.crc ^= byte;
+            for _ in 0..8 {
+                if (self.crc & 0x80) > 0 {
+                    self.crc = (self.crc << 1) ^ POLYNOMIAL;
+                } else {
+                    self.crc <<= 1;
+                }
+            }
+        }
+    }
+
+    /// Return the checksum of all data fed so far.
+    pub const fn finish(self) -> u8 {
+        self.crc
+    }
+}
+
+impl Default for Crc8 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Calculate the CRC8 checksum without branching on the individual bits.
+///
+/// The result is identical to [`calculate`]. This variant can be faster on cores where branches
+/// are expensive (e.g. Cortex-M0).
+pub fn calculate_branchless(data: &[u8]) -> u8 {
+    let mut crc = INIT;
     for byte in data {
         crc ^= byte;
         for _ in 0..8 {
-            if (crc & 0x80) > 0 {
-                crc = (crc << 1) ^ CRC8_POLYNOMIAL;
-            } else {
-                crc <<= 1;
-            }
+            crc = (crc << 1) ^ (POLYNOMIAL & 0u8.wrapping_sub(crc >> 7));
         }
     }
     crc
 }
 
+/// Calculate the CRC8 checksum and record the intermediate value after every byte.
+///
+/// `out[i]` receives the checksum of `data[..=i]`, so the last entry equals [`calculate`]. This is
+/// meant for debugging, not for the hot path. Returns `Error::InvalidBufferSize` if `out` is not
+/// as long as `data`.
+pub fn calculate_steps(data: &[u8], out: &mut [u8]) -> Result<(), Error> {
+    if out.len() != data.len() {
+        return Err(Error::InvalidBufferSize);
+    }
+    let mut crc = Crc8::new();
+    for (byte, step) in data.iter().zip(out.iter_mut()) {
+        crc.update(&[*byte]);
+        *step = crc.state();
+    }
+    Ok(())
+}
+
 /// Iterate over the provided buffer and validate the CRC8 checksums.
 ///
 /// The buffer must be in the form of `[d0, d1, crc01, d2, d3, crc23, ...]` where every third byte
@@ -44,9 +151,306 @@ pub fn validate(buf: &[u8]) -> Result<(), Error> {
     Ok(())
 }
 
+/// Return the number of complete words (two data bytes and a checksum byte) in `len` bytes.
+pub const fn words_in(len: usize) -> usize {
+    len / 3
+}
+
+/// Return the number of bytes needed for `words` words including their checksum bytes.
+///
+/// This is the inverse of [`words_in`].
+pub const fn bytes_for_words(words: usize) -> usize {
+    words * 3
+}
+
+/// Validate the CRC8 checksums of the first `words` words of a buffer, ignoring the rest.
+///
+/// The buffer must start with `[d0, d1, crc01, d2, d3, crc23, ...]`. Trailing bytes, e.g. the
+/// unused part of an over-sized read buffer, are not checked and need not be complete words. If
+/// a checksum is wrong, return `Err`. Returns `Error::InvalidBufferSize` if the buffer holds
+/// fewer than `words` words.
+pub fn validate_prefix(buf: &[u8], words: usize) -> Result<(), Error> {
+    let prefix = words
+        .checked_mul(3)
+        .and_then(|len| buf.get(..len))
+        .ok_or(Error::InvalidBufferSize)?;
+    validate(prefix)
+}
+
+/// Validate the CRC8 checksums of a buffer where every checksum covers `data_bytes_per_crc`
+/// preceding data bytes.
+///
+/// With `data_bytes_per_crc = 2` this is the same as [`validate`], with `4` every checksum covers
+/// two words: `[d0, d1, d2, d3, crc0123, ...]`. If a checksum is wrong, return `Err`. Returns
+/// `Error::InvalidBufferSize` if `data_bytes_per_crc` is 0 or if the buffer size is not a
+/// multiple of `data_bytes_per_crc + 1`.
+pub fn validate_block(buf: &[u8], data_bytes_per_crc: usize) -> Result<(), Error> {
+    let block_len = data_bytes_per_crc
+        .checked_add(1)
+        .ok_or(Error::InvalidBufferSize)?;
+    if data_bytes_per_crc == 0 || buf.len() % block_len != 0 {
+        return Err(Error::InvalidBufferSize);
+    }
+    for block in buf.chunks(block_len) {
+        if calculate(&block[..data_bytes_per_crc]) != block[data_bytes_per_crc] {
+            return Err(Error::CrcError);
+        }
+    }
+    Ok(())
+}
+
+/// Validate the checksums of a buffer with a caller-supplied checksum function.
+///
+/// The buffer must be in the form of `[d0, d1, crc01, d2, d3, crc23, ...]` like for [`validate`],
+/// but every checksum byte is compared against `crc_fn` of its word instead of the Sensirion
+/// CRC8. If a checksum is wrong, return `Err`. Returns `Error::InvalidBufferSize` if the buffer
+/// size is not a multiple of 3.
+pub fn validate_with_fn(buf: &[u8], crc_fn: impl Fn(&[u8; 2]) -> u8) -> Result<(), Error> {
+    if buf.len() % 3 != 0 {
+        return Err(Error::InvalidBufferSize);
+    }
+    for chunk in buf.chunks(3) {
+        if crc_fn(&[chunk[0], chunk[1]]) != chunk[2] {
+            return Err(Error::CrcError);
+        }
+    }
+    Ok(())
+}
+
+/// Validate the CRC8 checksums of a buffer which stores every checksum before its word.
+///
+/// The buffer must be in the form of `[crc01, d0, d1, crc23, d2, d3, ...]`. If a checksum is
+/// wrong, return `Err`. Returns `Error::InvalidBufferSize` if the buffer size is not a multiple
+/// of 3.
+pub fn validate_crc_first(buf: &[u8]) -> Result<(), Error> {
+    if buf.len() % 3 != 0 {
+        return Err(Error::InvalidBufferSize);
+    }
+    for chunk in buf.chunks(3) {
+        if calculate(&[chunk[1], chunk[2]]) != chunk[0] {
+            return Err(Error::CrcError);
+        }
+    }
+    Ok(())
+}
+
+/// Validate the CRC8 checksums of the buffer and record the positions of sentinel words.
+///
+/// The buffer must be in the form of `[d0, d1, crc01, d2, d3, crc23, ...]`. The checksums of all
+/// words, including the sentinels, are validated. The word index of every word equal to
+/// `sentinel` (e.g. `0xffff` for "no data") is written to `out_positions`, and the number of
+/// sentinel words found is returned.
+///
+/// Returns `Error::InvalidBufferSize` if the buffer size is not a multiple of 3 or if
+/// `out_positions` cannot hold all sentinel positions.
+pub fn validate_with_sentinel(
+    buf: &[u8],
+    sentinel: u16,
+    out_positions: &mut [usize],
+) -> Result<usize, Error> {
+    if buf.len() % 3 != 0 {
+        return Err(Error::InvalidBufferSize);
+    }
+    let mut found = 0;
+    for (index, chunk) in buf.chunks(3).enumerate() {
+        if calculate(&[chunk[0], chunk[1]]) != chunk[2] {
+            return Err(Error::CrcError);
+        }
+        if u16::from_be_bytes([chunk[0], chunk[1]]) == sentinel {
+            let position = out_positions
+                .get_mut(found)
+                .ok_or(Error::InvalidBufferSize)?;
+            *position = index;
+            found += 1;
+        }
+    }
+    Ok(found)
+}
+
+/// Validate the CRC8 checksums of a frame which stores all data words first and all checksums
+/// after them.
+///
+/// `crcs[i]` is the checksum of the word `data[2 * i..2 * i + 2]`. If a checksum is wrong, return
+/// `Err`. Returns `Error::InvalidBufferSize` if `data` is not exactly twice as long as `crcs`.
+pub fn validate_split(data: &[u8], crcs: &[u8]) -> Result<(), Error> {
+    if data.len() != 2 * crcs.len() {
+        return Err(Error::InvalidBufferSize);
+    }
+    for (word, crc) in data.chunks(2).zip(crcs) {
+        if calculate(word) != *crc {
+            return Err(Error::CrcError);
+        }
+    }
+    Ok(())
+}
+
+/// Compile time check that a buffer of `N` bytes holds complete words.
+struct AssertWordBuffer<const N: usize>;
+
+impl<const N: usize> AssertWordBuffer<N> {
+    const OK: () = assert!(N % 3 == 0, "Buffer must hold a multiple of 3 bytes");
+}
+
+/// Validate the CRC8 checksums of an owned array.
+///
+/// This works like [`validate`], but the buffer size is checked at compile time instead of
+/// panicking at runtime:
+///
+/// ```compile_fail
+/// use sensirion_i2c::crc8;
+///
+/// let _ = crc8::validate_array([0xbe, 0xef, 0x92, 0x00]);
+/// ```
+pub fn validate_array<const N: usize>(buf: [u8; N]) -> Result<(), Error> {
+    #[allow(clippy::let_unit_value)]
+    let () = AssertWordBuffer::<N>::OK;
+    validate(&buf)
+}
+
+/// Split an interleaved frame into its data bytes and its checksum bytes.
+///
+/// The frame must be in the form of `[d0, d1, crc01, d2, d3, crc23, ...]`. `data_out` receives
+/// `[d0, d1, d2, d3, ...]` and `crc_out` receives `[crc01, crc23, ...]`. The checksums are not
+/// validated.
+///
+/// Returns `Error::InvalidBufferSize` if the frame size is not a multiple of 3 or if the output
+/// buffers do not hold exactly two data bytes and one checksum byte per word.
+pub fn deinterleave(frame: &[u8], data_out: &mut [u8], crc_out: &mut [u8]) -> Result<(), Error> {
+    let words = frame.len() / 3;
+    if frame.len() % 3 != 0 || data_out.len() != 2 * words || crc_out.len() != words {
+        return Err(Error::InvalidBufferSize);
+    }
+    for ((chunk, data), crc) in frame
+        .chunks(3)
+        .zip(data_out.chunks_mut(2))
+        .zip(crc_out.iter_mut())
+    {
+        data.copy_from_slice(&chunk[..2]);
+        *crc = chunk[2];
+    }
+    Ok(())
+}
+
+/// Rebuild an interleaved frame from separate data and checksum bytes.
+///
+/// This is the counterpart of [`deinterleave`]. `out` receives `[d0, d1, crc01, d2, d3, crc23,
+/// ...]`. Every provided checksum is compared against the one calculated from its data bytes.
+///
+/// Returns `Error::InvalidBufferSize` if the buffers do not hold exactly two data bytes and one
+/// checksum byte per word and `Error::CrcError` if a provided checksum does not match. `out` is
+/// left untouched in the error case.
+pub fn interleave(data: &[u8], crcs: &[u8], out: &mut [u8]) -> Result<(), Error> {
+    if out.len() != 3 * crcs.len() {
+        return Err(Error::InvalidBufferSize);
+    }
+    validate_split(data, crcs)?;
+    interleave_unchecked(data, crcs, out)
+}
+
+/// Rebuild an interleaved frame from separate data and checksum bytes without validating the
+/// checksums.
+///
+/// Like [`interleave`], but the provided checksums are copied as-is.
+///
+/// Returns `Error::InvalidBufferSize` if the buffers do not hold exactly two data bytes and one
+/// checksum byte per word.
+pub fn interleave_unchecked(data: &[u8], crcs: &[u8], out: &mut [u8]) -> Result<(), Error> {
+    let words = crcs.len();
+    if data.len() != 2 * words || out.len() != 3 * words {
+        return Err(Error::InvalidBufferSize);
+    }
+    for ((chunk, data), crc) in out.chunks_mut(3).zip(data.chunks(2)).zip(crcs) {
+        chunk[..2].copy_from_slice(data);
+        chunk[2] = *crc;
+    }
+    Ok(())
+}
+
+/// Recompute the CRC8 checksum of every word in the buffer and record whether it matches.
+///
+/// The buffer must be in the form of `[d0, d1, crc01, d2, d3, crc23, ...]`. For every word,
+/// `out_mask` receives `true` if its checksum is correct and `false` otherwise.
+///
+/// Returns `Error::InvalidBufferSize` if the buffer size is not a multiple of 3 or if `out_mask`
+/// cannot hold one entry per word.
+pub fn crc_mask(buf: &[u8], out_mask: &mut [bool]) -> Result<(), Error> {
+    if buf.len() % 3 != 0 || out_mask.len() < buf.len() / 3 {
+        return Err(Error::InvalidBufferSize);
+    }
+    for (chunk, valid) in buf.chunks(3).zip(out_mask.iter_mut()) {
+        *valid = calculate(&[chunk[0], chunk[1]]) == chunk[2];
+    }
+    Ok(())
+}
+
+/// Return the length in bytes of the longest prefix of the buffer with valid CRC8 checksums.
+///
+/// The buffer must be in the form of `[d0, d1, crc01, d2, d3, crc23, ...]`. The returned length is
+/// always a multiple of 3; incomplete trailing chunks are never considered valid.
+pub fn valid_prefix_len(buf: &[u8]) -> usize {
+    buf.chunks_exact(3)
+        .take_while(|chunk| calculate(&[chunk[0], chunk[1]]) == chunk[2])
+        .count()
+        * 3
+}
+
+/// Iterator which reads words from a [`std::io::Read`] source and validates their CRC8 checksums.
+///
+/// The source must provide the words in the form of `[d0, d1, crc01, d2, d3, crc23, ...]`. Every
+/// item is either the decoded word or an error. A checksum mismatch is reported as an
+/// [`std::io::ErrorKind::InvalidData`] error wrapping [`Error::CrcError`], after which the
+/// iteration continues with the next word. The iteration ends at the end of the source.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ValidatingReader<R> {
+    reader: R,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ValidatingReader<R> {
+    /// Validate the words read from `reader`.
+    pub fn new(reader: R) -> Self {
+        ValidatingReader { reader }
+    }
+
+    /// Return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Iterator for ValidatingReader<R> {
+    type Item = std::io::Result<u16>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use std::io;
+
+        let mut chunk = [0; 3];
+        let mut filled = 0;
+        while filled < chunk.len() {
+            match self.reader.read(&mut chunk[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => return Some(Err(io::ErrorKind::UnexpectedEof.into())),
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        if calculate(&chunk[..2]) != chunk[2] {
+            return Some(Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                Error::CrcError,
+            )));
+        }
+        Some(Ok(u16::from_be_bytes([chunk[0], chunk[1]])))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::crc8;
+    use crate::test_util::FixedBuf;
 
     /// Test the crc function against the test value provided in the SHTC3 datasheet (section
     /// 5.10).
@@ -56,6 +460,42 @@ mod tests {
         assert_eq!(crc8::calculate(&[0xbe, 0xef]), 0x92);
     }
 
+    #[test]
+    fn crc8_branchless_matches_calculate() {
+        for byte in 0..=u8::MAX {
+            assert_eq!(
+                crc8::calculate_branchless(&[byte]),
+                crc8::calculate(&[byte])
+            );
+        }
+        assert_eq!(crc8::calculate_branchless(&[]), crc8::calculate(&[]));
+        assert_eq!(crc8::calculate_branchless(&[0xbe, 0xef]), 0x92);
+    }
+
+    #[test]
+    fn crc8_streaming_resume() {
+        let mut crc = crc8::Crc8::new();
+        crc.update(&[0xbe]);
+        let state = crc.state();
+
+        let mut crc = crc8::Crc8::from_state(state);
+        crc.update(&[0xef]);
+        assert_eq!(crc.finish(), crc8::calculate(&[0xbe, 0xef]));
+        assert_eq!(crc.finish(), 0x92);
+    }
+
+    #[test]
+    fn crc8_calculate_steps() {
+        let mut steps = [0; 2];
+        crc8::calculate_steps(&[0xbe, 0xef], &mut steps).unwrap();
+        assert_eq!(steps, [0x0c, 0x92]);
+
+        assert_eq!(
+            crc8::calculate_steps(&[0xbe, 0xef], &mut [0; 1]),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+    }
+
     #[test]
     fn crc8_validate_empty() {
         crc8::validate(&[]).unwrap();
@@ -78,4 +518,282 @@ mod tests {
             Err(crc8::Error::CrcError)
         );
     }
+
+    #[test]
+    fn crc8_validate_array() {
+        crc8::validate_array([0xbe, 0xef, 0x92, 0x00, 0x00, 0x81]).unwrap();
+        assert_eq!(
+            crc8::validate_array([0xbe, 0xef, 0x92, 0x00, 0x00, 0x80]),
+            Err(crc8::Error::CrcError)
+        );
+    }
+
+    #[test]
+    fn crc8_deinterleave() {
+        let mut data = [0; 4];
+        let mut crcs = [0; 2];
+        crc8::deinterleave(&[0xbe, 0xef, 0x92, 0x00, 0x00, 0x81], &mut data, &mut crcs).unwrap();
+        assert_eq!(data, [0xbe, 0xef, 0x00, 0x00]);
+        assert_eq!(crcs, [0x92, 0x81]);
+
+        assert_eq!(
+            crc8::deinterleave(&[0xbe, 0xef, 0x92], &mut data, &mut crcs),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+    }
+
+    #[test]
+    fn crc8_interleave_round_trip() {
+        let frame = [0xbe, 0xef, 0x92, 0x00, 0x00, 0x81];
+        let mut data = [0; 4];
+        let mut crcs = [0; 2];
+        crc8::deinterleave(&frame, &mut data, &mut crcs).unwrap();
+
+        let mut out = [0; 6];
+        crc8::interleave(&data, &crcs, &mut out).unwrap();
+        assert_eq!(out, frame);
+
+        crcs[1] = 0x00;
+        assert_eq!(
+            crc8::interleave(&data, &crcs, &mut out),
+            Err(crc8::Error::CrcError)
+        );
+        crc8::interleave_unchecked(&data, &crcs, &mut out).unwrap();
+        assert_eq!(out, [0xbe, 0xef, 0x92, 0x00, 0x00, 0x00]);
+
+        assert_eq!(
+            crc8::interleave(&data, &crcs[..1], &mut out),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+    }
+
+    #[test]
+    fn crc8_validate_with_fn() {
+        let xor = |word: &[u8; 2]| word[0] ^ word[1];
+        assert!(crc8::validate_with_fn(&[0xbe, 0xef, 0x51, 0x12, 0x34, 0x26], xor).is_ok());
+        assert_eq!(
+            crc8::validate_with_fn(&[0xbe, 0xef, 0x92], xor),
+            Err(crc8::Error::CrcError)
+        );
+        assert_eq!(
+            crc8::validate_with_fn(&[0xbe, 0xef], xor),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+    }
+
+    #[test]
+    fn crc8_crc_mask() {
+        let mut mask = [false; 3];
+        crc8::crc_mask(
+            &[0xbe, 0xef, 0x92, 0xbe, 0xef, 0x91, 0x00, 0x00, 0x81],
+            &mut mask,
+        )
+        .unwrap();
+        assert_eq!(mask, [true, false, true]);
+    }
+
+    #[test]
+    fn crc8_crc_mask_invalid_size() {
+        let mut mask = [false; 1];
+        assert_eq!(
+            crc8::crc_mask(&[0xbe, 0xef], &mut mask),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            crc8::crc_mask(&[0xbe, 0xef, 0x92, 0xbe, 0xef, 0x92], &mut mask),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+    }
+
+    #[test]
+    fn crc8_error_display() {
+        use core::fmt::Write;
+
+        let mut buf = FixedBuf::default();
+        write!(buf, "{}", crc8::Error::CrcError).unwrap();
+        assert_eq!(buf.as_str(), "CRC validation failed");
+
+        let mut buf = FixedBuf::default();
+        write!(buf, "{}", crc8::Error::InvalidBufferSize).unwrap();
+        assert_eq!(buf.as_str(), "invalid buffer size");
+    }
+
+    #[test]
+    fn crc8_valid_prefix_len() {
+        assert_eq!(crc8::valid_prefix_len(&[]), 0);
+        assert_eq!(crc8::valid_prefix_len(&[0xbe, 0xef, 0x92, 0xbe]), 3);
+        assert_eq!(
+            crc8::valid_prefix_len(&[
+                0xbe, 0xef, 0x92, 0x00, 0x00, 0x81, 0xbe, 0xef, 0x91, 0xbe, 0xef, 0x92
+            ]),
+            6
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn crc8_validating_reader() {
+        use std::io::{Cursor, ErrorKind};
+
+        let capture = Cursor::new([0xbe, 0xef, 0x92, 0xbe, 0xef, 0x91, 0x00, 0x00, 0x81, 0x12]);
+        let mut reader = crc8::ValidatingReader::new(capture);
+
+        assert_eq!(reader.next().unwrap().unwrap(), 0xbeef);
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<crc8::Error>(),
+            Some(&crc8::Error::CrcError)
+        );
+        assert_eq!(reader.next().unwrap().unwrap(), 0x0000);
+        assert_eq!(
+            reader.next().unwrap().unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn crc8_validate_split() {
+        // Valid CRCs
+        crc8::validate_split(&[0xbe, 0xef, 0x00, 0x00], &[0x92, 0x81]).unwrap();
+
+        // Invalid CRC
+        assert_eq!(
+            crc8::validate_split(&[0xbe, 0xef, 0x00, 0x00], &[0x92, 0x82]),
+            Err(crc8::Error::CrcError)
+        );
+
+        // Invalid size
+        assert_eq!(
+            crc8::validate_split(&[0xbe, 0xef, 0x00], &[0x92, 0x81]),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+    }
+
+    #[test]
+    fn crc8_validate_with_sentinel() {
+        let frame = [0xbe, 0xef, 0x92, 0xff, 0xff, 0xac, 0x00, 0x00, 0x81];
+        let mut positions = [0; 2];
+
+        assert_eq!(
+            crc8::validate_with_sentinel(&frame, 0xffff, &mut positions),
+            Ok(1)
+        );
+        assert_eq!(positions[0], 1);
+
+        // Invalid CRC
+        assert_eq!(
+            crc8::validate_with_sentinel(&[0xff, 0xff, 0x00], 0xffff, &mut positions),
+            Err(crc8::Error::CrcError)
+        );
+
+        // Too many sentinels
+        assert_eq!(
+            crc8::validate_with_sentinel(&frame, 0xffff, &mut []),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+    }
+
+    #[test]
+    fn crc8_validate_crc_first() {
+        // Valid CRC
+        crc8::validate_crc_first(&[0x92, 0xbe, 0xef, 0x81, 0x00, 0x00]).unwrap();
+
+        // Invalid CRC
+        assert_eq!(
+            crc8::validate_crc_first(&[0x92, 0xbe, 0xef, 0x80, 0x00, 0x00]),
+            Err(crc8::Error::CrcError)
+        );
+
+        // Default layout is not accepted
+        assert_eq!(
+            crc8::validate_crc_first(&[0xbe, 0xef, 0x92]),
+            Err(crc8::Error::CrcError)
+        );
+    }
+
+    #[test]
+    fn crc8_parameters_match_reference() {
+        assert_eq!(crc8::POLYNOMIAL, 0x31);
+        assert_eq!(crc8::INIT, 0xff);
+
+        // Check value of CRC-8/NRSC-5 from the catalogue of parametrised CRC algorithms
+        assert_eq!(crc8::calculate(b"123456789"), 0xf7);
+
+        // Table driven reference implementation, built from the public parameters only
+        let mut table = [0u8; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            let mut crc = i as u8;
+            for _ in 0..8 {
+                crc = if crc & 0x80 != 0 {
+                    (crc << 1) ^ crc8::POLYNOMIAL
+                } else {
+                    crc << 1
+                };
+            }
+            *entry = crc;
+        }
+        let reference = |data: &[u8]| {
+            data.iter()
+                .fold(crc8::INIT, |crc, byte| table[usize::from(crc ^ byte)])
+        };
+        for data in [&[][..], &[0xbe, 0xef], &[0x00, 0x00], b"123456789"] {
+            assert_eq!(crc8::calculate(data), reference(data));
+        }
+    }
+
+    #[test]
+    fn crc8_validate_prefix() {
+        let buf = [0xbe, 0xef, 0x92, 0x12, 0x34, 0x37, 0xde, 0xad, 0x00, 0xff];
+        assert!(crc8::validate_prefix(&buf, 2).is_ok());
+        assert!(crc8::validate_prefix(&buf, 0).is_ok());
+        assert_eq!(crc8::validate_prefix(&buf, 3), Err(crc8::Error::CrcError));
+        assert_eq!(
+            crc8::validate_prefix(&buf, 4),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            crc8::validate_prefix(&buf, usize::MAX / 2),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+    }
+
+    #[test]
+    fn crc8_words_in() {
+        assert_eq!(crc8::words_in(0), 0);
+        assert_eq!(crc8::words_in(3), 1);
+        assert_eq!(crc8::words_in(5), 1);
+        assert_eq!(crc8::words_in(9), 3);
+        for words in 0..4 {
+            assert_eq!(crc8::words_in(crc8::bytes_for_words(words)), words);
+        }
+    }
+
+    #[test]
+    fn crc8_validate_block() {
+        let frame = [0xbe, 0xef, 0x92, 0x00, 0x00, 0x81];
+        assert!(crc8::validate_block(&frame, 2).is_ok());
+
+        let blocks = [0xbe, 0xef, 0x12, 0x34, 0x22, 0x00, 0x00, 0x56, 0x78, 0x2b];
+        assert!(crc8::validate_block(&blocks, 4).is_ok());
+        let mut corrupted = blocks;
+        corrupted[9] = 0x00;
+        assert_eq!(
+            crc8::validate_block(&corrupted, 4),
+            Err(crc8::Error::CrcError)
+        );
+        assert_eq!(
+            crc8::validate_block(&blocks[..9], 4),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            crc8::validate_block(&blocks, 0),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+        assert_eq!(
+            crc8::validate_block(&blocks, usize::MAX),
+            Err(crc8::Error::InvalidBufferSize)
+        );
+    }
 }
@@ -1,24 +1,474 @@
 //! Helper functions for I²C communication.
 
 use crate::crc8;
+use embedded_hal::delay::DelayNs;
 use embedded_hal::i2c;
 
+/// Default I²C addresses of Sensirion sensors.
+pub mod addresses {
+    /// SCD30 CO₂ sensor
+    pub const SCD30: u8 = 0x61;
+    /// SCD4x CO₂ sensors
+    pub const SCD4X: u8 = 0x62;
+    /// SEN5x environmental sensor nodes
+    pub const SEN5X: u8 = 0x69;
+    /// SGP30 gas sensor
+    pub const SGP30: u8 = 0x58;
+    /// SGP40 VOC sensor
+    pub const SGP40: u8 = 0x59;
+    /// SGP41 VOC and NOx sensor
+    pub const SGP41: u8 = 0x59;
+    /// SHT3x humidity and temperature sensors (ADDR pin low)
+    pub const SHT3X: u8 = 0x44;
+    /// SHT4x humidity and temperature sensors (A variants)
+    pub const SHT4X: u8 = 0x44;
+    /// SHTC3 humidity and temperature sensor
+    pub const SHTC3: u8 = 0x70;
+    /// SPS30 particulate matter sensor
+    pub const SPS30: u8 = 0x69;
+}
+
+/// Convert an 8-bit (pre-shifted) address, as printed in some datasheets, to the 7-bit address
+/// expected by `embedded-hal`.
+pub const fn addr_8bit_to_7bit(addr8: u8) -> u8 {
+    addr8 >> 1
+}
+
+/// Convert a 7-bit address to the 8-bit address byte of a write transfer.
+pub const fn addr_7bit_to_8bit_write(addr7: u8) -> u8 {
+    addr7 << 1
+}
+
+/// Convert a 7-bit address to the 8-bit address byte of a read transfer.
+pub const fn addr_7bit_to_8bit_read(addr7: u8) -> u8 {
+    (addr7 << 1) | 1
+}
+
+/// Check whether two 7-bit addresses map to the same 8-bit address bytes.
+///
+/// Distinct valid 7-bit addresses never collide. A collision means that at least one of them has
+/// its top bit set, which usually is an 8-bit address passed where a 7-bit one was expected, e.g.
+/// `0x88` (the SHT3x write byte) collides with `0x08` because the top bit is shifted out.
+pub const fn addresses_collide(a: u8, b: u8) -> bool {
+    addr_7bit_to_8bit_write(a) == addr_7bit_to_8bit_write(b)
+}
+
 /// All possible errors in this crate
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[non_exhaustive]
 pub enum Error<I: i2c::ErrorType> {
     I2cWrite(I::Error),
     I2cRead(I::Error),
     Crc,
+    /// The provided buffer is too small for the data to be read or written
+    InvalidBufferSize,
+    /// The bus returned fewer bytes than requested
+    ShortRead,
+    /// All data bytes of the frame were 0x00 or all were 0xFF, which hints at a disconnected or
+    /// stuck bus
+    SuspiciousFrame,
+}
+
+impl<I: i2c::ErrorType> core::fmt::Display for Error<I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::I2cWrite(_) => f.write_str("I2C write failed"),
+            Error::I2cRead(_) => f.write_str("I2C read failed"),
+            Error::Crc => f.write_str("CRC validation failed"),
+            Error::InvalidBufferSize => f.write_str("invalid buffer size"),
+            Error::ShortRead => f.write_str("I2C read returned too few bytes"),
+            Error::SuspiciousFrame => f.write_str("suspicious frame data"),
+        }
+    }
 }
 
+#[cfg(feature = "std")]
+impl<I: i2c::ErrorType + core::fmt::Debug> std::error::Error for Error<I> {}
+
 impl<I: i2c::ErrorType> From<crc8::Error> for Error<I> {
     fn from(err: crc8::Error) -> Error<I> {
         match err {
             crc8::Error::CrcError => Error::Crc,
+            crc8::Error::InvalidBufferSize => Error::InvalidBufferSize,
+        }
+    }
+}
+
+/// Maximum number of words the generic decoding read helpers can read in one transaction.
+///
+/// Stable Rust cannot size an array from an expression like `3 * N`, so the helpers which are
+/// generic over the number of words (e.g. [`read_n_words`], [`read_f32_array`] and
+/// [`read_struct`]) read through a scratch buffer of `3 * MAX_READ_WORDS` = 96 bytes on the
+/// stack. 32 words cover the longest responses of current Sensirion sensors, e.g. the 20 words of
+/// an SPS30 measurement, with some headroom. Helpers with a fixed response size (e.g.
+/// [`read_u32`] or [`read_config_bool`]) only use as much stack as their response needs.
+pub const MAX_READ_WORDS: usize = 32;
+
+/// Size of the scratch buffer of the generic decoding read helpers
+const SCRATCH_LEN: usize = 3 * MAX_READ_WORDS;
+
+/// Types which can be decoded from a fixed number of validated words.
+///
+/// ```
+/// use sensirion_i2c::i2c::FromWords;
+///
+/// struct Measurement {
+///     temperature: u16,
+///     humidity: u16,
+/// }
+///
+/// impl FromWords for Measurement {
+///     const WORDS: usize = 2;
+///
+///     fn from_words(words: &[u16]) -> Self {
+///         Measurement {
+///             temperature: words[0],
+///             humidity: words[1],
+///         }
+///     }
+/// }
+/// ```
+pub trait FromWords: Sized {
+    /// Number of words the type is decoded from
+    const WORDS: usize;
+
+    /// Decode the type from exactly `WORDS` words.
+    fn from_words(words: &[u16]) -> Self;
+}
+
+/// Define a measurement struct together with a function reading it from the sensor.
+///
+/// Every field is decoded from one word, in the given order, with the `from_be_bytes` function
+/// of its type (e.g. `u16` or `i16`). The generated `read` function writes `command`, waits
+/// `delay_us` microseconds and reads and validates the response. The struct also implements
+/// [`FromWords`], so it can be used with [`read_struct`].
+///
+/// ```
+/// use embedded_hal_mock::eh1::delay::NoopDelay;
+/// use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction};
+///
+/// sensirion_i2c::define_measurement! {
+///     /// SCD4x measurement
+///     #[derive(Debug)]
+///     pub struct Measurement {
+///         pub co2: u16,
+///         pub temperature: i16,
+///         pub humidity: u16,
+///     }
+///     command = 0xec05;
+///     delay_us = 1_000;
+/// }
+///
+/// let expectations = [
+///     Transaction::write(0x62, vec![0xec, 0x05]),
+///     Transaction::read(0x62, vec![0x12, 0x34, 0x37, 0xbe, 0xef, 0x92, 0x00, 0x00, 0x81]),
+/// ];
+/// let mut i2c_mock = I2cMock::new(&expectations);
+/// let measurement = Measurement::read(&mut i2c_mock, 0x62, &mut NoopDelay).unwrap();
+/// assert_eq!(measurement.co2, 0x1234);
+/// i2c_mock.done();
+/// ```
+#[macro_export]
+macro_rules! define_measurement {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_meta:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+        command = $command:expr;
+        delay_us = $delay_us:expr;
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$field_meta])* $field_vis $field: $ty),*
+        }
+
+        impl $crate::i2c::FromWords for $name {
+            const WORDS: usize = [$(stringify!($field)),*].len();
+
+            fn from_words(words: &[u16]) -> Self {
+                let mut words = words.iter();
+                $name {
+                    $($field: <$ty>::from_be_bytes(
+                        words.next().expect("too few words").to_be_bytes(),
+                    )),*
+                }
+            }
+        }
+
+        impl $name {
+            /// Command which triggers the read of this measurement
+            pub const COMMAND: u16 = $command;
+
+            /// Time in microseconds between the command and the read
+            pub const DELAY_US: u32 = $delay_us;
+
+            /// Write the command, wait for the response and read and validate it.
+            pub fn read<I, D>(
+                i2c: &mut I,
+                addr: u8,
+                delay: &mut D,
+            ) -> ::core::result::Result<Self, $crate::i2c::Error<I>>
+            where
+                I: $crate::__private::I2c,
+                D: $crate::__private::DelayNs,
+            {
+                $crate::i2c::write_command_u16(i2c, addr, Self::COMMAND)
+                    .map_err($crate::i2c::Error::I2cWrite)?;
+                delay.delay_us(Self::DELAY_US);
+                $crate::i2c::read_struct(i2c, addr)
+            }
+        }
+    };
+}
+
+/// I²C buses which report how many bytes a read actually received.
+///
+/// The `embedded-hal` I²C traits assume that a successful read always fills the whole buffer.
+/// HALs which can detect a truncated read can implement this trait to be used with
+/// [`read_exact_words_with_crc`].
+pub trait CountedRead: i2c::ErrorType {
+    /// Read into `read` and return the number of bytes actually received.
+    fn read_counted(&mut self, address: u8, read: &mut [u8]) -> Result<usize, Self::Error>;
+}
+
+/// Sensor command which knows how long the sensor needs to execute it.
+///
+/// Coupling the opcode with its datasheet execution time lets [`execute_timed`] wait the right
+/// amount of time before reading the response.
+pub trait TimedCommand {
+    /// The u16 opcode of the command
+    fn command(&self) -> u16;
+
+    /// Time in microseconds to wait between writing the command and reading the response
+    fn measure_delay_us(&self) -> u32;
+}
+
+/// Measurement precision (repeatability) modes offered by many Sensirion sensors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    /// Lowest precision, shortest measurement duration and lowest power consumption
+    Low,
+    /// Medium precision
+    Medium,
+    /// Highest precision, longest measurement duration
+    High,
+}
+
+/// Mapping of [`Precision`] modes to the command opcodes of a specific sensor.
+pub trait PrecisionCommands {
+    /// Return the measurement command for the given precision.
+    fn command(&self, precision: Precision) -> u16;
+}
+
+/// Description of a complete read operation: command, response length and execution time.
+///
+/// ```
+/// use sensirion_i2c::i2c::ReadSpec;
+///
+/// // SHTC3 measurement in normal mode, temperature first
+/// const MEASURE: ReadSpec = ReadSpec {
+///     command: 0x7866,
+///     words: 2,
+///     delay_us: 12_100,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadSpec {
+    /// The u16 command which triggers the response
+    pub command: u16,
+    /// Number of words in the response
+    pub words: usize,
+    /// Time in microseconds to wait between writing the command and reading the response
+    pub delay_us: u32,
+}
+
+impl ReadSpec {
+    /// Write the command, wait for `delay_us` and read the response into the first `words * 3`
+    /// bytes of `out`, validating the CRC8 checksum.
+    ///
+    /// Returns `Error::InvalidBufferSize` if `out` is too small for the response.
+    pub fn execute_read<I: i2c::I2c, D: DelayNs>(
+        &self,
+        i2c: &mut I,
+        addr: u8,
+        delay: &mut D,
+        out: &mut [u8],
+    ) -> Result<(), Error<I>> {
+        let data = self
+            .words
+            .checked_mul(3)
+            .and_then(|len| out.get_mut(..len))
+            .ok_or(Error::InvalidBufferSize)?;
+        measure(i2c, addr, self.command, delay, self.delay_us, data)
+    }
+}
+
+/// Running CRC statistics, updated by [`read_words_with_crc_stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CrcStats {
+    /// Number of reads whose CRC was checked
+    pub total: u64,
+    /// Number of reads which failed the CRC check
+    pub errors: u64,
+}
+
+/// Strategy for growing the delay between two attempts of a [`RetryPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// Wait `delay_us` between all attempts
+    Fixed,
+    /// Double the delay after every failed attempt, starting with `delay_us`
+    Exponential,
+}
+
+/// Retry configuration shared by the retrying helpers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one. `0` is treated like `1`.
+    pub max_attempts: u8,
+    /// Delay in microseconds before the first retry
+    pub delay_us: u32,
+    /// How the delay grows between subsequent retries
+    pub backoff: Backoff,
+}
+
+impl RetryPolicy {
+    /// Return the delay in microseconds to wait after the given (1-based) failed attempt.
+    pub(crate) fn retry_delay_us(&self, attempt: u8) -> u32 {
+        match self.backoff {
+            Backoff::Fixed => self.delay_us,
+            Backoff::Exponential => {
+                let factor = 1u32
+                    .checked_shl(u32::from(attempt.saturating_sub(1)))
+                    .unwrap_or(u32::MAX);
+                self.delay_us.saturating_mul(factor)
+            }
         }
     }
 }
 
+/// I²C bus wrapper which calls a hook before every transaction.
+///
+/// The wrapper implements [`i2c::I2c`], so it can be passed to all helpers in this module. The
+/// hook receives the wrapped bus, which allows a driver to e.g. lower the bus speed through the
+/// HAL before talking to a sensor which misbehaves at 400 kHz.
+#[derive(Debug)]
+pub struct WithPreOp<I, F> {
+    i2c: I,
+    pre_op: F,
+}
+
+impl<I: i2c::I2c, F: FnMut(&mut I)> WithPreOp<I, F> {
+    /// Wrap `i2c` so that `pre_op` is called before every transaction.
+    pub fn new(i2c: I, pre_op: F) -> Self {
+        WithPreOp { i2c, pre_op }
+    }
+
+    /// Return the wrapped bus.
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
+
+impl<I: i2c::ErrorType, F> i2c::ErrorType for WithPreOp<I, F> {
+    type Error = I::Error;
+}
+
+impl<I: i2c::I2c, F: FnMut(&mut I)> i2c::I2c for WithPreOp<I, F> {
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        (self.pre_op)(&mut self.i2c);
+        self.i2c.read(address, read)
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        (self.pre_op)(&mut self.i2c);
+        self.i2c.write(address, write)
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        (self.pre_op)(&mut self.i2c);
+        self.i2c.write_read(address, write, read)
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        (self.pre_op)(&mut self.i2c);
+        self.i2c.transaction(address, operations)
+    }
+}
+
+/// A 16-bit sensor command.
+///
+/// Drivers can use `Command` to keep opcodes and data values apart. The `u16` based helpers stay
+/// available for existing code, the methods of `Command` are their typed counterparts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Command(pub u16);
+
+impl Command {
+    /// Return the two command bytes in bus order.
+    pub const fn to_be_bytes(self) -> [u8; 2] {
+        self.0.to_be_bytes()
+    }
+
+    /// Write the command to the I²C bus, like [`write_command_u16`].
+    pub fn write<I: i2c::I2c>(self, i2c: &mut I, addr: u8) -> Result<(), I::Error> {
+        write_command_u16(i2c, addr, self.0)
+    }
+
+    /// Write the command followed by the CRC8 checksum of the command bytes to the I²C bus, like
+    /// [`write_command_u16_crc`].
+    pub fn write_with_crc<I: i2c::I2c>(self, i2c: &mut I, addr: u8) -> Result<(), I::Error> {
+        write_command_u16_crc(i2c, addr, self.0)
+    }
+}
+
+impl From<u16> for Command {
+    fn from(command: u16) -> Self {
+        Command(command)
+    }
+}
+
+impl From<Command> for u16 {
+    fn from(command: Command) -> Self {
+        command.0
+    }
+}
+
+/// A 16-bit data word, as opposed to a [`Command`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataWord(pub u16);
+
+impl DataWord {
+    /// Return the two data bytes in bus order followed by their CRC8 checksum.
+    pub fn with_crc(self) -> [u8; 3] {
+        let [hi, lo] = self.0.to_be_bytes();
+        [hi, lo, crc8::calculate(&[hi, lo])]
+    }
+}
+
+impl From<u16> for DataWord {
+    fn from(word: u16) -> Self {
+        DataWord(word)
+    }
+}
+
+/// Return the number of bytes of a frame with an u16 command followed by `arg_words` argument
+/// words, each protected by a CRC8 checksum.
+///
+/// This can be used to size the buffer for [`write_command_with_bytes`].
+pub const fn command_frame_len(arg_words: usize) -> usize {
+    2 + arg_words * 3
+}
+
 /// Write an u16 command to the I²C bus.
 #[deprecated(note = "Please use `write_command_u16` instead.")]
 pub fn write_command<I: i2c::I2c>(i2c: &mut I, addr: u8, command: u16) -> Result<(), I::Error> {
@@ -35,9 +485,106 @@ pub fn write_command_u16<I: i2c::I2c>(i2c: &mut I, addr: u8, command: u16) -> Re
     i2c.write(addr, &command.to_be_bytes())
 }
 
+/// Write an u16 command followed by the CRC8 checksum of the command bytes to the I²C bus.
+///
+/// Some sensors expect the command word itself to be protected, making the write
+/// `[cmd_hi, cmd_lo, crc]`.
+pub fn write_command_u16_crc<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+) -> Result<(), I::Error> {
+    let [hi, lo] = command.to_be_bytes();
+    i2c.write(addr, &[hi, lo, crc8::calculate(&[hi, lo])])
+}
+
+/// Write an u16 command to the I²C bus, retrying on failure.
+///
+/// This helps on multi-master buses, where a write can lose arbitration. Every error is retried
+/// according to `policy`. If all attempts fail, the error of the last attempt is returned.
+pub fn write_command_u16_retry<I: i2c::I2c, D: DelayNs>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+    delay: &mut D,
+    policy: &RetryPolicy,
+) -> Result<(), I::Error> {
+    let mut attempt = 1;
+    loop {
+        match write_command_u16(i2c, addr, command) {
+            Ok(()) => return Ok(()),
+            Err(err) if attempt >= policy.max_attempts => return Err(err),
+            Err(_) => {
+                delay.delay_us(policy.retry_delay_us(attempt));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Write an u16 wake-up command to the I²C bus, ignoring a missing acknowledge.
+///
+/// Some sensors (e.g. the SHTC3) do not acknowledge the wake-up command while they are asleep.
+/// A NACK is therefore treated as success, all other errors are returned.
+///
+/// This relies on the HAL reporting NACKs as [`i2c::ErrorKind::NoAcknowledge`]. With HALs which
+/// report them as [`i2c::ErrorKind::Other`], the error is returned and must be handled by the
+/// caller.
+pub fn wakeup<I: i2c::I2c>(i2c: &mut I, addr: u8, command: u16) -> Result<(), I::Error> {
+    match write_command_u16(i2c, addr, command) {
+        Err(err) if matches!(i2c::Error::kind(&err), i2c::ErrorKind::NoAcknowledge(_)) => Ok(()),
+        result => result,
+    }
+}
+
+/// Execute a script of u16 commands, waiting the paired delay in microseconds after each one.
+///
+/// This lets drivers keep their initialization sequence as data, e.g. in a
+/// `const INIT: &[(u16, u32)]`. Stops at and returns the first write error.
+pub fn run_init_script<I: i2c::I2c, D: DelayNs>(
+    i2c: &mut I,
+    addr: u8,
+    script: &[(u16, u32)],
+    delay: &mut D,
+) -> Result<(), I::Error> {
+    for &(command, delay_us) in script {
+        write_command_u16(i2c, addr, command)?;
+        delay.delay_us(delay_us);
+    }
+    Ok(())
+}
+
+/// Write an u16 command followed by a payload to the I²C bus, adding a CRC8 checksum after
+/// every payload word.
+///
+/// The frame is assembled in a stack buffer of `N` bytes, which must hold the two command bytes
+/// and three bytes per payload word. Returns `Error::InvalidBufferSize` if the payload length is
+/// odd or if the frame does not fit into the buffer. With an empty payload, only the command is
+/// written, so the write is never empty.
+pub fn write_command_with_bytes<const N: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+    payload: &[u8],
+) -> Result<(), Error<I>> {
+    let len = 2 + payload.len() / 2 * 3;
+    if payload.len() % 2 != 0 || len > N {
+        return Err(Error::InvalidBufferSize);
+    }
+
+    let mut buf = [0; N];
+    buf[..2].copy_from_slice(&command.to_be_bytes());
+    for (word, chunk) in payload.chunks(2).zip(buf[2..].chunks_mut(3)) {
+        chunk[..2].copy_from_slice(word);
+        chunk[2] = crc8::calculate(word);
+    }
+    i2c.write(addr, &buf[..len]).map_err(Error::I2cWrite)
+}
+
 /// Read data into the provided buffer and validate the CRC8 checksum.
 ///
-/// If the checksum is wrong, return `Error::Crc`.
+/// If the checksum is wrong, return `Error::Crc`. An empty buffer returns `Ok(())` without
+/// accessing the bus, since some HALs reject zero-length reads.
 ///
 /// # Panics
 ///
@@ -52,73 +599,2345 @@ pub fn read_words_with_crc<I: i2c::I2c>(
         data.len() % 3 == 0,
         "Buffer must hold a multiple of 3 bytes"
     );
+    if data.is_empty() {
+        return Ok(());
+    }
     i2c.read(addr, data).map_err(Error::I2cRead)?;
     crc8::validate(data)?;
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::i2c;
-
-    use embedded_hal_mock as hal;
-    use hal::eh1::i2c::{Mock as I2cMock, Transaction};
-
-    #[test]
-    fn read_words_with_crc() {
-        let mut buf = [0; 3];
-
-        // Valid CRC
-        {
-            let expectations = [Transaction::read(0x58, vec![0xBE, 0xEF, 0x92])];
-            let mut mock = I2cMock::new(&expectations);
-            i2c::read_words_with_crc(&mut mock, 0x58, &mut buf).unwrap();
-            assert_eq!(buf, [0xbe, 0xef, 0x92]);
-            mock.done();
-        }
-
-        // Invalid CRC
-        {
-            let expectations = [Transaction::read(0x58, vec![0xBE, 0xEF, 0x00])];
-            let mut mock = I2cMock::new(&expectations);
-            match i2c::read_words_with_crc(&mut mock, 0x58, &mut buf) {
-                Err(i2c::Error::Crc) => {}
-                Err(_) => panic!("Invalid error: Must be Crc"),
-                Ok(_) => panic!("CRC check did not fail"),
-            }
-            assert_eq!(buf, [0xbe, 0xef, 0x00]); // Buf was changed
-            mock.done();
+/// Read data into the provided buffer, reject suspicious frames and validate the CRC8 checksum.
+///
+/// A frame whose data bytes are all 0x00 or all 0xFF usually comes from a disconnected or held
+/// bus rather than from the sensor, and an all-zero frame even passes the CRC check. Such frames
+/// return `Error::SuspiciousFrame`, which takes precedence over `Error::Crc`. Otherwise this
+/// behaves like [`read_words_with_crc`].
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_with_crc_sanity<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+) -> Result<(), Error<I>> {
+    assert!(
+        data.len() % 3 == 0,
+        "Buffer must hold a multiple of 3 bytes"
+    );
+    if data.is_empty() {
+        return Ok(());
+    }
+    i2c.read(addr, data).map_err(Error::I2cRead)?;
+    for stuck in [0x00, 0xff] {
+        if data.chunks(3).all(|chunk| chunk[..2] == [stuck, stuck]) {
+            return Err(Error::SuspiciousFrame);
         }
     }
+    crc8::validate(data)?;
+    Ok(())
+}
 
-    #[test]
-    #[allow(deprecated)]
-    fn write_command() {
-        let expectations = [Transaction::write(0x58, vec![0xab, 0xcd])];
-        let mut mock = I2cMock::new(&expectations);
-
-        i2c::write_command(&mut mock, 0x58, 0xabcd).unwrap();
-
-        mock.done();
+/// Write a command to and read the response from several devices, continuing after failures.
+///
+/// Every op is an `(addr, command, buffer)` tuple. For each op, the command is written and the
+/// response is read into the buffer and validated like with [`read_words_with_crc`]. The outcome
+/// is stored at the same index in `results`, so a failing device does not abort the batch.
+///
+/// # Panics
+///
+/// Panics if `results` is not as long as `ops`, or if a buffer size is not a multiple of 3.
+pub fn read_batch<I: i2c::I2c>(
+    i2c: &mut I,
+    ops: &mut [(u8, u16, &mut [u8])],
+    results: &mut [Result<(), Error<I>>],
+) {
+    assert_eq!(ops.len(), results.len(), "There must be one result per op");
+    for ((addr, command, data), result) in ops.iter_mut().zip(results.iter_mut()) {
+        *result = write_command_u16(i2c, *addr, *command)
+            .map_err(Error::I2cWrite)
+            .and_then(|()| read_words_with_crc(i2c, *addr, data));
     }
+}
 
-    #[test]
-    fn write_command_u8() {
-        let expectations = [Transaction::write(0x58, vec![0xab])];
-        let mut mock = I2cMock::new(&expectations);
-
-        i2c::write_command_u8(&mut mock, 0x58, 0xab).unwrap();
-
-        mock.done();
+/// Read data into the provided buffer using several reads of at most `max_chunk` bytes each, then
+/// validate the CRC8 checksum.
+///
+/// This allows reading long frames with HALs which limit the transfer size. Every chunk is
+/// rounded down to a multiple of 3 bytes, so no word is split between two reads. Returns
+/// `Error::InvalidBufferSize` if `max_chunk` is smaller than 3.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_chunked<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    max_chunk: usize,
+) -> Result<(), Error<I>> {
+    assert!(
+        data.len() % 3 == 0,
+        "Buffer must hold a multiple of 3 bytes"
+    );
+    let chunk_len = max_chunk / 3 * 3;
+    if chunk_len == 0 {
+        return Err(Error::InvalidBufferSize);
     }
+    for chunk in data.chunks_mut(chunk_len) {
+        i2c.read(addr, chunk).map_err(Error::I2cRead)?;
+    }
+    crc8::validate(data)?;
+    Ok(())
+}
 
-    #[test]
-    fn write_command_u16() {
-        let expectations = [Transaction::write(0x58, vec![0xab, 0xcd])];
-        let mut mock = I2cMock::new(&expectations);
-
-        i2c::write_command_u16(&mut mock, 0x58, 0xabcd).unwrap();
-
+/// Read data into the provided buffer using several reads of at most `max_chunk` bytes each,
+/// validating the CRC8 checksums of every chunk as soon as it has been read.
+///
+/// Like [`read_words_chunked`], but stops at the first chunk which fails to read or validate, so
+/// long frames fail fast. The error is returned together with the index of the word it concerns:
+/// the first word with a wrong checksum, or the first word of a chunk whose read failed. Returns
+/// `Error::InvalidBufferSize` (for word 0) if `max_chunk` is smaller than 3.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_and_validate_streaming<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    max_chunk: usize,
+) -> Result<(), (usize, Error<I>)> {
+    assert!(
+        data.len() % 3 == 0,
+        "Buffer must hold a multiple of 3 bytes"
+    );
+    let chunk_len = max_chunk / 3 * 3;
+    if chunk_len == 0 {
+        return Err((0, Error::InvalidBufferSize));
+    }
+    for (index, chunk) in data.chunks_mut(chunk_len).enumerate() {
+        let first_word = index * chunk_len / 3;
+        i2c.read(addr, chunk)
+            .map_err(|err| (first_word, Error::I2cRead(err)))?;
+        let valid_words = crc8::valid_prefix_len(chunk) / 3;
+        if valid_words * 3 != chunk.len() {
+            return Err((first_word + valid_words, Error::Crc));
+        }
+    }
+    Ok(())
+}
+
+/// Write an u16 command, wait for `wait_us` microseconds and read the response into the provided
+/// buffer, validating the CRC8 checksum.
+///
+/// This is the common single-shot measurement pattern. The delay is an `embedded-hal`
+/// [`DelayNs`]; `i2c_async::measure` is the equivalent for the `embedded-hal-async` traits.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn measure<I: i2c::I2c, D: DelayNs>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+    delay: &mut D,
+    wait_us: u32,
+    data: &mut [u8],
+) -> Result<(), Error<I>> {
+    write_command_u16(i2c, addr, command).map_err(Error::I2cWrite)?;
+    delay.delay_us(wait_us);
+    read_words_with_crc(i2c, addr, data)
+}
+
+/// Repeatedly read frames into the provided buffer at a fixed cadence, validating their CRC8
+/// checksums.
+///
+/// This is the steady-state read loop of sensors in continuous measurement mode. Every valid
+/// frame is passed to `on_frame`, which returns `false` to stop the loop. Between two reads, the
+/// loop waits for `interval_us` microseconds. The first failing read ends the loop with its error.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_continuous<I: i2c::I2c, D: DelayNs, F: FnMut(&[u8]) -> bool>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    delay: &mut D,
+    interval_us: u32,
+    mut on_frame: F,
+) -> Result<(), Error<I>> {
+    loop {
+        read_words_with_crc(i2c, addr, data)?;
+        if !on_frame(data) {
+            return Ok(());
+        }
+        delay.delay_us(interval_us);
+    }
+}
+
+/// Write a [`TimedCommand`], wait for its execution time and read the response into the provided
+/// buffer, validating the CRC8 checksum.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn execute_timed<C: TimedCommand, I: i2c::I2c, D: DelayNs>(
+    i2c: &mut I,
+    addr: u8,
+    command: &C,
+    delay: &mut D,
+    data: &mut [u8],
+) -> Result<(), Error<I>> {
+    measure(
+        i2c,
+        addr,
+        command.command(),
+        delay,
+        command.measure_delay_us(),
+        data,
+    )
+}
+
+/// Read data into the provided buffer, apply `transform` to every data word and validate the CRC8
+/// checksum over the transformed words.
+///
+/// This supports encoded frames whose checksums are calculated over the decoded data rather than
+/// the bytes on the wire. The checksum bytes are left untouched.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_with_crc_transform<I: i2c::I2c, F: Fn(&mut [u8; 2])>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    transform: F,
+) -> Result<(), Error<I>> {
+    assert!(
+        data.len() % 3 == 0,
+        "Buffer must hold a multiple of 3 bytes"
+    );
+    if data.is_empty() {
+        return Ok(());
+    }
+    i2c.read(addr, data).map_err(Error::I2cRead)?;
+    for chunk in data.chunks_mut(3) {
+        let mut word = [chunk[0], chunk[1]];
+        transform(&mut word);
+        chunk[..2].copy_from_slice(&word);
+    }
+    crc8::validate(data)?;
+    Ok(())
+}
+
+/// Read data into the provided buffer, make sure it was filled completely and validate the CRC8
+/// checksum.
+///
+/// If fewer bytes than the buffer size were received, return `Error::ShortRead`. If the checksum
+/// is wrong, return `Error::Crc`. An empty buffer returns `Ok(())` without accessing the bus.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_exact_words_with_crc<I: CountedRead>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+) -> Result<(), Error<I>> {
+    assert!(
+        data.len() % 3 == 0,
+        "Buffer must hold a multiple of 3 bytes"
+    );
+    if data.is_empty() {
+        return Ok(());
+    }
+    let received = i2c.read_counted(addr, data).map_err(Error::I2cRead)?;
+    if received < data.len() {
+        return Err(Error::ShortRead);
+    }
+    crc8::validate(data)?;
+    Ok(())
+}
+
+/// Read data into the provided buffer, validate the CRC8 checksum and return whether the
+/// data-ready bit in the first word is set.
+///
+/// The first word is considered ready if any of the bits in `ready_mask` is set. Returns
+/// `Error::InvalidBufferSize` if the buffer does not hold at least one word.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_with_ready_bit<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    ready_mask: u16,
+) -> Result<bool, Error<I>> {
+    if data.len() < 3 {
+        return Err(Error::InvalidBufferSize);
+    }
+    read_words_with_crc(i2c, addr, data)?;
+    Ok(u16::from_be_bytes([data[0], data[1]]) & ready_mask != 0)
+}
+
+/// Poll the data-ready status of a sensor until it reports ready data.
+///
+/// Every poll writes `command` and reads one status word, validating its CRC8 checksum. The data
+/// is considered ready if any of the bits in `ready_mask` is set (e.g. `0x07ff` for the SCD4x
+/// `get_data_ready_status` command). Between two polls, the delay of `policy` is waited. Returns
+/// `Ok(true)` as soon as the sensor is ready and `Ok(false)` if it is still not ready after
+/// `policy.max_attempts` polls. Bus and CRC errors are returned immediately.
+pub fn poll_until_ready<I: i2c::I2c, D: DelayNs>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+    ready_mask: u16,
+    delay: &mut D,
+    policy: &RetryPolicy,
+) -> Result<bool, Error<I>> {
+    let mut attempt = 1;
+    loop {
+        write_command_u16(i2c, addr, command).map_err(Error::I2cWrite)?;
+        let mut status = [0; 3];
+        if read_words_with_ready_bit(i2c, addr, &mut status, ready_mask)? {
+            return Ok(true);
+        }
+        if attempt >= policy.max_attempts {
+            return Ok(false);
+        }
+        delay.delay_us(policy.retry_delay_us(attempt));
+        attempt += 1;
+    }
+}
+
+/// Read data into the provided buffer, validate the CRC8 checksum and pass every decoded word to
+/// `f`.
+///
+/// `f` is called with the index and the value of each word, in order. It is only called once the
+/// whole buffer passed validation.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_for_each<I: i2c::I2c, F: FnMut(usize, u16)>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    mut f: F,
+) -> Result<(), Error<I>> {
+    read_words_with_crc(i2c, addr, data)?;
+    for (index, chunk) in data.chunks(3).enumerate() {
+        f(index, u16::from_be_bytes([chunk[0], chunk[1]]));
+    }
+    Ok(())
+}
+
+/// Read data into the provided buffer and validate the CRC8 checksum, measuring how long it took.
+///
+/// `now` is called once before and once after the read and must return a monotonic tick count.
+/// On success, the elapsed number of ticks is returned.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_with_crc_timed<I: i2c::I2c, T: FnMut() -> u64>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    mut now: T,
+) -> Result<u64, Error<I>> {
+    let start = now();
+    read_words_with_crc(i2c, addr, data)?;
+    Ok(now().wrapping_sub(start))
+}
+
+/// Read data into the provided buffer and validate the CRC8 checksum, recording the result in
+/// `stats`.
+///
+/// Reads which fail on the bus are not counted, since their checksum could not be checked.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_with_crc_stats<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    stats: &mut CrcStats,
+) -> Result<(), Error<I>> {
+    let result = read_words_with_crc(i2c, addr, data);
+    match result {
+        Ok(()) => stats.total += 1,
+        Err(Error::Crc) => {
+            stats.total += 1;
+            stats.errors += 1;
+        }
+        Err(_) => {}
+    }
+    result
+}
+
+/// Read data into the provided buffer and validate the CRC8 checksum, tolerating up to `max_bad`
+/// corrupted words.
+///
+/// The indices of the words with a wrong checksum are written to the start of `bad_out` and
+/// their count is returned. The data of these words must not be used. If more than `max_bad`
+/// words are corrupted, return `Error::Crc`. Returns `Error::InvalidBufferSize` without accessing
+/// the bus if `bad_out` cannot hold `max_bad` indices.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_tolerant<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    max_bad: usize,
+    bad_out: &mut [usize],
+) -> Result<usize, Error<I>> {
+    assert!(
+        data.len() % 3 == 0,
+        "Buffer must hold a multiple of 3 bytes"
+    );
+    if bad_out.len() < max_bad {
+        return Err(Error::InvalidBufferSize);
+    }
+    if data.is_empty() {
+        return Ok(0);
+    }
+    i2c.read(addr, data).map_err(Error::I2cRead)?;
+
+    let mut bad = 0;
+    for (index, chunk) in data.chunks(3).enumerate() {
+        if crc8::calculate(&chunk[..2]) != chunk[2] {
+            if bad == max_bad {
+                return Err(Error::Crc);
+            }
+            bad_out[bad] = index;
+            bad += 1;
+        }
+    }
+    Ok(bad)
+}
+
+/// Read data into the provided buffer and validate the CRC8 checksum, retrying on failure.
+///
+/// Both bus errors and CRC errors are retried according to `policy`. If all attempts fail, the
+/// error of the last attempt is returned.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_with_crc_retry<I: i2c::I2c, D: DelayNs>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    delay: &mut D,
+    policy: &RetryPolicy,
+) -> Result<(), Error<I>> {
+    read_words_with_crc_retry_if(i2c, addr, data, delay, policy, |_| true)
+}
+
+/// Read data into the provided buffer and validate the CRC8 checksum, retrying CRC errors and
+/// the bus errors selected by `should_retry`.
+///
+/// Since `I::Error` is opaque to this crate, the caller decides which bus errors are worth
+/// retrying, e.g. by their [`i2c::Error::kind`]. CRC errors are always retried. Retries follow
+/// `policy`; if all attempts fail, the error of the last attempt is returned. A bus error for
+/// which `should_retry` returns `false` is returned immediately.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub fn read_words_with_crc_retry_if<I: i2c::I2c, D: DelayNs, F: Fn(&I::Error) -> bool>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    delay: &mut D,
+    policy: &RetryPolicy,
+    should_retry: F,
+) -> Result<(), Error<I>> {
+    let mut attempt = 1;
+    loop {
+        match read_words_with_crc(i2c, addr, data) {
+            Ok(()) => return Ok(()),
+            Err(err) if attempt >= policy.max_attempts => return Err(err),
+            Err(Error::I2cRead(err)) if !should_retry(&err) => return Err(Error::I2cRead(err)),
+            Err(_) => {
+                delay.delay_us(policy.retry_delay_us(attempt));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Read `words.len()` words through a scratch buffer of `S` bytes, validate their CRC8 checksums
+/// and decode them as big-endian `u16`.
+///
+/// Returns `Error::InvalidBufferSize` if the scratch buffer cannot hold the words.
+fn read_decoded_words<const S: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    words: &mut [u16],
+) -> Result<(), Error<I>> {
+    let mut buf = [0; S];
+    let buf = buf
+        .get_mut(..3 * words.len())
+        .ok_or(Error::InvalidBufferSize)?;
+    read_words_for_each(i2c, addr, buf, |index, word| words[index] = word)
+}
+
+/// Compile time check that `N` values of `W` words each fit into the scratch buffer of the
+/// decoding read helpers.
+struct AssertReadWords<const N: usize, const W: usize>;
+
+impl<const N: usize, const W: usize> AssertReadWords<N, W> {
+    const OK: () = assert!(
+        N * W <= MAX_READ_WORDS,
+        "Cannot read more than MAX_READ_WORDS words"
+    );
+
+    /// Like `OK`, but leaves room for one additional status word.
+    const OK_WITH_STATUS: () = assert!(
+        N * W < MAX_READ_WORDS,
+        "Cannot read more than MAX_READ_WORDS words including the status word"
+    );
+}
+
+/// Compile time check that a [`FromWords`] type fits into the scratch buffer of the decoding read
+/// helpers.
+struct AssertFromWords<T>(core::marker::PhantomData<T>);
+
+impl<T: FromWords> AssertFromWords<T> {
+    const OK: () = assert!(
+        T::WORDS <= MAX_READ_WORDS,
+        "Cannot read more than MAX_READ_WORDS words"
+    );
+}
+
+/// Read `W` words, validate their CRC8 checksums and decode them as big-endian `u16`.
+///
+/// Since the buffer size is derived from `W`, this cannot fail because of an invalid buffer size.
+/// Reading more than [`MAX_READ_WORDS`] words is rejected at compile time:
+///
+/// ```compile_fail
+/// # use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+/// # use sensirion_i2c::i2c;
+/// # let mut i2c_mock = I2cMock::new(&[]);
+/// let words = i2c::read_n_words::<33, _>(&mut i2c_mock, 0x12);
+/// ```
+pub fn read_n_words<const W: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+) -> Result<[u16; W], Error<I>> {
+    #[allow(clippy::let_unit_value)]
+    let () = AssertReadWords::<W, 1>::OK;
+    let mut words = [0; W];
+    read_decoded_words::<SCRATCH_LEN, _>(i2c, addr, &mut words)?;
+    Ok(words)
+}
+
+/// Words which are known to have passed CRC8 validation.
+///
+/// A `Validated` can only be obtained from [`read_validated`], so code receiving one statically
+/// knows that its words were checked. It cannot be constructed from arbitrary words:
+///
+/// ```compile_fail
+/// use sensirion_i2c::i2c::Validated;
+///
+/// let words = Validated([0x1234, 0x5678]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Validated<const N: usize>([u16; N]);
+
+impl<const N: usize> Validated<N> {
+    /// Return the validated words.
+    pub fn into_inner(self) -> [u16; N] {
+        self.0
+    }
+}
+
+/// Read `N` words, validate their CRC8 checksums and return them as [`Validated`] words.
+///
+/// Reading more than [`MAX_READ_WORDS`] words is rejected at compile time.
+pub fn read_validated<const N: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+) -> Result<Validated<N>, Error<I>> {
+    read_n_words(i2c, addr).map(Validated)
+}
+
+/// Read `N` words, validate their CRC8 checksums and decode them as little-endian `u16`.
+///
+/// The checksums are calculated over the bytes in wire order, so validation is the same as for
+/// [`read_n_words`]. Only the decoding differs, for bridges to little-endian peripherals.
+pub fn read_words_le<const N: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+) -> Result<[u16; N], Error<I>> {
+    let words = read_n_words::<N, _>(i2c, addr)?;
+    Ok(words.map(u16::swap_bytes))
+}
+
+/// Write an u16 command and read `N` words in one write-read transaction with a repeated start,
+/// validate their CRC8 checksums and decode them as big-endian `u16`.
+///
+/// Since the write and the read are a single bus transaction, a bus failure in either phase is
+/// returned as `Error::I2cRead`. Reading more than [`MAX_READ_WORDS`] words is rejected at compile
+/// time.
+pub fn query_words<const N: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+) -> Result<[u16; N], Error<I>> {
+    #[allow(clippy::let_unit_value)]
+    let () = AssertReadWords::<N, 1>::OK;
+    let mut buf = [0; SCRATCH_LEN];
+    let buf = &mut buf[..3 * N];
+    i2c.write_read(addr, &command.to_be_bytes(), buf)
+        .map_err(Error::I2cRead)?;
+    crc8::validate(buf)?;
+    let mut words = [0; N];
+    for (word, chunk) in words.iter_mut().zip(buf.chunks(3)) {
+        *word = u16::from_be_bytes([chunk[0], chunk[1]]);
+    }
+    Ok(words)
+}
+
+/// Decode the two data bytes of a word as a big-endian signed `i16`.
+pub fn decode_i16_be(bytes: &[u8; 2]) -> i16 {
+    i16::from_be_bytes(*bytes)
+}
+
+/// Read `N` words, validate their CRC8 checksums and decode them as big-endian signed `i16`.
+pub fn read_i16_words<const N: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+) -> Result<[i16; N], Error<I>> {
+    let words = read_n_words::<N, _>(i2c, addr)?;
+    Ok(words.map(|word| decode_i16_be(&word.to_be_bytes())))
+}
+
+/// Read three words, validate their CRC8 checksums and return them as a tuple.
+///
+/// This is the shape of e.g. the SCD4x measurement frame (CO₂, temperature, humidity).
+pub fn read_triplet<I: i2c::I2c>(i2c: &mut I, addr: u8) -> Result<(u16, u16, u16), Error<I>> {
+    let mut words = [0; 3];
+    read_decoded_words::<9, _>(i2c, addr, &mut words)?;
+    let [a, b, c] = words;
+    Ok((a, b, c))
+}
+
+/// Read `D` data words followed by one status word, validate their CRC8 checksums and return
+/// both.
+///
+/// Reading more than [`MAX_READ_WORDS`] words including the status word is rejected at compile
+/// time:
+///
+/// ```compile_fail
+/// # use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+/// # use sensirion_i2c::i2c;
+/// # let mut i2c_mock = I2cMock::new(&[]);
+/// let frame = i2c::read_data_and_status::<32, _>(&mut i2c_mock, 0x12);
+/// ```
+pub fn read_data_and_status<const D: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+) -> Result<([u16; D], u16), Error<I>> {
+    #[allow(clippy::let_unit_value)]
+    let () = AssertReadWords::<D, 1>::OK_WITH_STATUS;
+    let mut buf = [0; SCRATCH_LEN];
+    let buf = &mut buf[..3 * (D + 1)];
+    let mut data = [0; D];
+    let mut status = 0;
+    read_words_for_each(i2c, addr, buf, |index, word| match data.get_mut(index) {
+        Some(value) => *value = word,
+        None => status = word,
+    })?;
+    Ok((data, status))
+}
+
+/// Assemble a `u32` from two words, most significant word first.
+pub fn decode_u32_be(words: &[u16; 2]) -> u32 {
+    (u32::from(words[0]) << 16) | u32::from(words[1])
+}
+
+/// Read two words, validate their CRC8 checksums and assemble them into a big-endian `u32`.
+pub fn read_u32<I: i2c::I2c>(i2c: &mut I, addr: u8) -> Result<u32, Error<I>> {
+    let mut words = [0; 2];
+    read_decoded_words::<6, _>(i2c, addr, &mut words)?;
+    Ok(decode_u32_be(&words))
+}
+
+/// Read `N` words, validate their CRC8 checksums and scale them to physical values.
+///
+/// Every `(offset, span)` pair in `scales` converts the raw word at the same index to
+/// `offset + span * raw / 65535`, the conversion formula used in most Sensirion datasheets.
+pub fn read_scaled<const N: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    scales: [(f32, f32); N],
+) -> Result<[f32; N], Error<I>> {
+    let words = read_n_words::<N, _>(i2c, addr)?;
+    let mut values = [0.0; N];
+    for ((value, word), (offset, span)) in values.iter_mut().zip(words).zip(scales) {
+        *value = scale(word, offset, span);
+    }
+    Ok(values)
+}
+
+/// Convert a raw word to `offset + span * raw / 65535`.
+fn scale(raw: u16, offset: f32, span: f32) -> f32 {
+    offset + span * (f32::from(raw) / 65535.0)
+}
+
+/// Linear conversion of raw temperature and humidity words to physical values.
+///
+/// Each value is computed as `offset + span * raw / 65535`, like in [`read_scaled`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    /// Temperature at a raw value of 0, usually in °C
+    pub t_offset: f32,
+    /// Temperature range covered by the raw values
+    pub t_span: f32,
+    /// Relative humidity at a raw value of 0, usually in %RH
+    pub rh_offset: f32,
+    /// Relative humidity range covered by the raw values
+    pub rh_span: f32,
+}
+
+/// Read a temperature and a humidity word, validate their CRC8 checksums and convert them with
+/// `cal`.
+///
+/// Returns `(temperature, humidity)`. Sensors which send humidity first must swap the result.
+pub fn read_th<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    cal: &Calibration,
+) -> Result<(f32, f32), Error<I>> {
+    let mut words = [0; 2];
+    read_decoded_words::<6, _>(i2c, addr, &mut words)?;
+    let [t, rh] = words;
+    Ok((
+        scale(t, cal.t_offset, cal.t_span),
+        scale(rh, cal.rh_offset, cal.rh_span),
+    ))
+}
+
+/// Read `N` floats of two words each, validate their CRC8 checksums and decode them.
+///
+/// Every float is transmitted as IEEE-754 single precision value, most significant word first.
+/// This is e.g. the shape of the SPS30 measurement frame. Reading more than
+/// [`MAX_READ_WORDS`] words is rejected at compile time.
+pub fn read_f32_array<const N: usize, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+) -> Result<[f32; N], Error<I>> {
+    #[allow(clippy::let_unit_value)]
+    let () = AssertReadWords::<N, 2>::OK;
+    let mut buf = [0; SCRATCH_LEN];
+    let mut values = [0.0; N];
+    let mut high = 0;
+    read_words_for_each(i2c, addr, &mut buf[..6 * N], |index, word| {
+        if index % 2 == 0 {
+            high = word;
+        } else {
+            values[index / 2] = f32::from_bits(decode_u32_be(&[high, word]));
+        }
+    })?;
+    Ok(values)
+}
+
+/// Read `T::WORDS` words, validate their CRC8 checksums and decode them into `T`.
+///
+/// Types with more than [`MAX_READ_WORDS`] words are rejected at compile time:
+///
+/// ```compile_fail
+/// # use embedded_hal_mock::eh1::i2c::Mock as I2cMock;
+/// use sensirion_i2c::i2c::{self, FromWords};
+///
+/// struct Huge;
+///
+/// impl FromWords for Huge {
+///     const WORDS: usize = 33;
+///
+///     fn from_words(_words: &[u16]) -> Self {
+///         Huge
+///     }
+/// }
+///
+/// # let mut i2c_mock = I2cMock::new(&[]);
+/// let huge = i2c::read_struct::<Huge, _>(&mut i2c_mock, 0x12);
+/// ```
+pub fn read_struct<T: FromWords, I: i2c::I2c>(i2c: &mut I, addr: u8) -> Result<T, Error<I>> {
+    #[allow(clippy::let_unit_value)]
+    let () = AssertFromWords::<T>::OK;
+    let mut words = [0; MAX_READ_WORDS];
+    let words = &mut words[..T::WORDS];
+    read_decoded_words::<SCRATCH_LEN, _>(i2c, addr, words)?;
+    Ok(T::from_words(words))
+}
+
+/// Write a command and read a frame whose first word holds the number of data words that follow.
+///
+/// The length word is read and validated first, then the announced number of data words is read
+/// into `scratch` and validated. Returns the validated data words (including their CRC bytes).
+///
+/// If `scratch` cannot hold the announced number of words, return `Error::InvalidBufferSize`.
+pub fn read_length_prefixed<'a, I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    read_cmd: u16,
+    scratch: &'a mut [u8],
+) -> Result<&'a [u8], Error<I>> {
+    write_command_u16(i2c, addr, read_cmd).map_err(Error::I2cWrite)?;
+
+    let mut length = [0; 3];
+    read_words_with_crc(i2c, addr, &mut length)?;
+    // Compare in words, `words * 3` may overflow a 16-bit `usize`
+    let words = usize::from(u16::from_be_bytes([length[0], length[1]]));
+    if words > scratch.len() / 3 {
+        return Err(Error::InvalidBufferSize);
+    }
+
+    let data = &mut scratch[..words * 3];
+    read_words_with_crc(i2c, addr, data)?;
+    Ok(data)
+}
+
+/// Write an u16 command to `write_addr`, then read words from `read_addr` and validate their CRC8
+/// checksums.
+///
+/// This is for bridge devices which use different addresses for the write and the read phase.
+/// The read behaves like [`read_words_with_crc`].
+pub fn read_words_with_crc_at<I: i2c::I2c>(
+    i2c: &mut I,
+    write_addr: u8,
+    read_addr: u8,
+    command: u16,
+    data: &mut [u8],
+) -> Result<(), Error<I>> {
+    write_command_u16(i2c, write_addr, command).map_err(Error::I2cWrite)?;
+    read_words_with_crc(i2c, read_addr, data)
+}
+
+/// Write a single register pointer byte, then read words and validate their CRC8 checksums.
+///
+/// Both happen in separate transactions, as required by register-pointer-style devices. The
+/// read behaves like [`read_words_with_crc`].
+pub fn read_after_pointer<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    pointer: u8,
+    data: &mut [u8],
+) -> Result<(), Error<I>> {
+    write_command_u8(i2c, addr, pointer).map_err(Error::I2cWrite)?;
+    read_words_with_crc(i2c, addr, data)
+}
+
+/// Check that the device at `addr` reports a serial number starting with `expected_prefix`.
+///
+/// Writes `serial_cmd`, reads the first word of the serial number (its most significant 16 bits)
+/// and validates its CRC8 checksum. Returns whether it matches `expected_prefix`. The rest of the
+/// serial number is not read.
+pub fn verify_device<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    serial_cmd: u16,
+    expected_prefix: u16,
+) -> Result<bool, Error<I>> {
+    write_command_u16(i2c, addr, serial_cmd).map_err(Error::I2cWrite)?;
+    let mut prefix = [0];
+    read_decoded_words::<3, _>(i2c, addr, &mut prefix)?;
+    let [prefix] = prefix;
+    Ok(prefix == expected_prefix)
+}
+
+/// Write an u16 command and read a boolean configuration word, validating its CRC8 checksum.
+///
+/// Any non-zero word is `true`. This is e.g. the shape of the automatic self-calibration state of
+/// Sensirion CO₂ sensors.
+pub fn read_config_bool<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+) -> Result<bool, Error<I>> {
+    write_command_u16(i2c, addr, command).map_err(Error::I2cWrite)?;
+    let mut word = [0];
+    read_decoded_words::<3, _>(i2c, addr, &mut word)?;
+    let [word] = word;
+    Ok(word != 0)
+}
+
+/// Write an u16 command followed by a boolean configuration word (`0x0001` or `0x0000`) and its
+/// CRC8 checksum.
+pub fn write_config_bool<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+    value: bool,
+) -> Result<(), Error<I>> {
+    write_command_with_bytes::<5, _>(i2c, addr, command, &u16::from(value).to_be_bytes())
+}
+
+/// Compare the data bytes of two frames, ignoring their checksum bytes.
+///
+/// Both frames must be in the form of `[d0, d1, crc01, d2, d3, crc23, ...]`. Frames of different
+/// lengths are never equal.
+pub fn frames_data_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && a.chunks(3)
+            .zip(b.chunks(3))
+            .all(|(a, b)| a[..a.len().min(2)] == b[..b.len().min(2)])
+}
+
+/// List the positions where `actual` differs from `expected` as `(index, expected, actual)`.
+///
+/// Only the common prefix of both slices is compared, so a length mismatch must be checked
+/// separately. Intended for informative failure messages in verification tests.
+#[cfg(feature = "std")]
+pub fn diff_words(expected: &[u16], actual: &[u16]) -> Vec<(usize, u16, u16)> {
+    expected
+        .iter()
+        .zip(actual)
+        .enumerate()
+        .filter(|(_, (expected, actual))| expected != actual)
+        .map(|(index, (expected, actual))| (index, *expected, *actual))
+        .collect()
+}
+
+/// Validate a frame and extract the words of one channel of an interleaved multi-channel frame.
+///
+/// The frame must be in the form of `[d0, d1, crc01, d2, d3, crc23, ...]`. Every `stride`-th word
+/// starting at word `channel` belongs to the channel and is decoded into `out`, e.g. `channel = 1`
+/// and `stride = 2` picks words 1, 3, 5, ... of a dual-channel frame. The checksums of all words
+/// are validated, not only the ones of the extracted channel.
+///
+/// Returns `crc8::Error::InvalidBufferSize` if the frame size is not a multiple of 3, if `channel`
+/// is not smaller than `stride` or if `out` does not match the number of words of the channel.
+pub fn extract_channel(
+    buf: &[u8],
+    channel: usize,
+    stride: usize,
+    out: &mut [u16],
+) -> Result<(), crc8::Error> {
+    let words = buf.len() / 3;
+    if buf.len() % 3 != 0 || channel >= stride {
+        return Err(crc8::Error::InvalidBufferSize);
+    }
+    // Round up without overflowing for huge strides, `stride > channel >= 0` holds here
+    let remaining = words.saturating_sub(channel);
+    let channel_words = remaining / stride + usize::from(remaining % stride != 0);
+    if out.len() != channel_words {
+        return Err(crc8::Error::InvalidBufferSize);
+    }
+    crc8::validate(buf)?;
+    for (word, chunk) in out
+        .iter_mut()
+        .zip(buf.chunks(3).skip(channel).step_by(stride))
+    {
+        *word = u16::from_be_bytes([chunk[0], chunk[1]]);
+    }
+    Ok(())
+}
+
+/// Write a frame as space separated lowercase hex bytes (e.g. `be ef 92`) into `out`.
+///
+/// Returns the number of bytes written. Returns `Err` if `out` is too small, in which case `out`
+/// is left untouched. Useful for logging frames without pulling in a formatting crate.
+pub fn fmt_frame(buf: &[u8], out: &mut [u8]) -> Result<usize, core::fmt::Error> {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+
+    let len = (3 * buf.len()).saturating_sub(1);
+    let out = out.get_mut(..len).ok_or(core::fmt::Error)?;
+    for (i, byte) in buf.iter().enumerate() {
+        out[3 * i] = HEX[usize::from(byte >> 4)];
+        out[3 * i + 1] = HEX[usize::from(byte & 0x0f)];
+        if let Some(separator) = out.get_mut(3 * i + 2) {
+            *separator = b' ';
+        }
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::i2c;
+    use crate::test_util::FixedBuf;
+
+    use embedded_hal::delay::DelayNs;
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_mock as hal;
+    use hal::eh1::i2c::{Mock as I2cMock, Transaction};
+
+    /// Delay which records all requested delays in microseconds.
+    #[derive(Default)]
+    struct RecordingDelay {
+        sleeps_us: Vec<u32>,
+    }
+
+    impl DelayNs for RecordingDelay {
+        fn delay_ns(&mut self, ns: u32) {
+            self.sleeps_us.push(ns / 1000);
+        }
+
+        fn delay_us(&mut self, us: u32) {
+            self.sleeps_us.push(us);
+        }
+    }
+
+    #[test]
+    fn read_words_with_crc() {
+        let mut buf = [0; 3];
+
+        // Valid CRC
+        {
+            let expectations = [Transaction::read(0x58, vec![0xBE, 0xEF, 0x92])];
+            let mut mock = I2cMock::new(&expectations);
+            i2c::read_words_with_crc(&mut mock, 0x58, &mut buf).unwrap();
+            assert_eq!(buf, [0xbe, 0xef, 0x92]);
+            mock.done();
+        }
+
+        // Invalid CRC
+        {
+            let expectations = [Transaction::read(0x58, vec![0xBE, 0xEF, 0x00])];
+            let mut mock = I2cMock::new(&expectations);
+            match i2c::read_words_with_crc(&mut mock, 0x58, &mut buf) {
+                Err(i2c::Error::Crc) => {}
+                Err(_) => panic!("Invalid error: Must be Crc"),
+                Ok(_) => panic!("CRC check did not fail"),
+            }
+            assert_eq!(buf, [0xbe, 0xef, 0x00]); // Buf was changed
+            mock.done();
+        }
+    }
+
+    #[test]
+    fn addresses() {
+        assert_eq!(i2c::addresses::SHTC3, 0x70);
+        assert_eq!(i2c::addresses::SCD4X, 0x62);
+        assert_eq!(i2c::addresses::SGP40, 0x59);
+        assert_eq!(i2c::addresses::SPS30, 0x69);
+    }
+
+    #[test]
+    fn address_conversion() {
+        assert_eq!(i2c::addr_8bit_to_7bit(0xe0), 0x70);
+        assert_eq!(i2c::addr_8bit_to_7bit(0xe1), 0x70);
+        assert_eq!(i2c::addr_7bit_to_8bit_write(0x70), 0xe0);
+        assert_eq!(i2c::addr_7bit_to_8bit_read(0x70), 0xe1);
+    }
+
+    #[test]
+    fn addresses_collide() {
+        assert!(i2c::addresses_collide(0x88, 0x08));
+        assert!(i2c::addresses_collide(0x44, 0x44));
+        assert!(!i2c::addresses_collide(0x44, 0x45));
+    }
+
+    #[test]
+    fn error_display() {
+        use core::fmt::Write;
+
+        let errors: [(i2c::Error<I2cMock>, &str); 6] = [
+            (i2c::Error::I2cWrite(ErrorKind::Other), "I2C write failed"),
+            (i2c::Error::I2cRead(ErrorKind::Other), "I2C read failed"),
+            (i2c::Error::Crc, "CRC validation failed"),
+            (i2c::Error::InvalidBufferSize, "invalid buffer size"),
+            (i2c::Error::ShortRead, "I2C read returned too few bytes"),
+            (i2c::Error::SuspiciousFrame, "suspicious frame data"),
+        ];
+        for (error, message) in errors.iter() {
+            let mut buf = FixedBuf::default();
+            write!(buf, "{}", error).unwrap();
+            assert_eq!(buf.as_str(), *message);
+        }
+    }
+
+    #[test]
+    fn read_batch() {
+        let mut buf1 = [0; 3];
+        let mut buf2 = [0; 3];
+        let mut buf3 = [0; 3];
+
+        let expectations = [
+            Transaction::write(0x58, vec![0xe3, 0x69]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92]),
+            Transaction::write(0x59, vec![0xe3, 0x69]).with_error(ErrorKind::Other),
+            Transaction::write(0x5a, vec![0xe3, 0x69]),
+            Transaction::read(0x5a, vec![0x12, 0x34, 0x37]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut ops: [(u8, u16, &mut [u8]); 3] = [
+            (0x58, 0xe369, &mut buf1),
+            (0x59, 0xe369, &mut buf2),
+            (0x5a, 0xe369, &mut buf3),
+        ];
+        let mut results = [Ok(()), Ok(()), Ok(())];
+        i2c::read_batch(&mut mock, &mut ops, &mut results);
+
+        assert!(results[0].is_ok());
+        match results[1] {
+            Err(i2c::Error::I2cWrite(ErrorKind::Other)) => {}
+            _ => panic!("Invalid result: Must be I2cWrite"),
+        }
+        assert!(results[2].is_ok());
+        assert_eq!(buf1, [0xbe, 0xef, 0x92]);
+        assert_eq!(buf3, [0x12, 0x34, 0x37]);
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_chunked() {
+        let mut buf = [0; 9];
+
+        let expectations = [
+            Transaction::read(0x58, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        i2c::read_words_chunked(&mut mock, 0x58, &mut buf, 8).unwrap();
+        assert_eq!(buf, [0x12, 0x34, 0x37, 0x56, 0x78, 0x7d, 0xbe, 0xef, 0x92]);
+        mock.done();
+
+        let mut mock = I2cMock::new(&[]);
+        match i2c::read_words_chunked(&mut mock, 0x58, &mut buf, 2) {
+            Err(i2c::Error::InvalidBufferSize) => {}
+            _ => panic!("Invalid result: Must be InvalidBufferSize"),
+        }
+        mock.done();
+    }
+
+    #[test]
+    fn measure() {
+        let mut buf = [0; 3];
+
+        let expectations = [
+            Transaction::write(0x70, vec![0x78, 0x66]),
+            Transaction::read(0x70, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay::default();
+        i2c::measure(&mut mock, 0x70, 0x7866, &mut delay, 12_100, &mut buf).unwrap();
+        assert_eq!(buf, [0xbe, 0xef, 0x92]);
+        assert_eq!(delay.sleeps_us, [12_100]);
+        mock.done();
+    }
+
+    struct Sht3xSingleShot;
+
+    impl i2c::PrecisionCommands for Sht3xSingleShot {
+        fn command(&self, precision: i2c::Precision) -> u16 {
+            match precision {
+                i2c::Precision::Low => 0x2416,
+                i2c::Precision::Medium => 0x240b,
+                i2c::Precision::High => 0x2400,
+            }
+        }
+    }
+
+    #[test]
+    fn precision_commands() {
+        use i2c::PrecisionCommands;
+
+        assert_eq!(Sht3xSingleShot.command(i2c::Precision::Low), 0x2416);
+        assert_eq!(Sht3xSingleShot.command(i2c::Precision::Medium), 0x240b);
+        assert_eq!(Sht3xSingleShot.command(i2c::Precision::High), 0x2400);
+    }
+
+    enum ShtCommand {
+        MeasureNormal,
+        MeasureLowPower,
+    }
+
+    impl i2c::TimedCommand for ShtCommand {
+        fn command(&self) -> u16 {
+            match self {
+                ShtCommand::MeasureNormal => 0x7866,
+                ShtCommand::MeasureLowPower => 0x609c,
+            }
+        }
+
+        fn measure_delay_us(&self) -> u32 {
+            match self {
+                ShtCommand::MeasureNormal => 12_100,
+                ShtCommand::MeasureLowPower => 800,
+            }
+        }
+    }
+
+    #[test]
+    fn execute_timed() {
+        let mut buf = [0; 3];
+
+        let expectations = [
+            Transaction::write(0x70, vec![0x78, 0x66]),
+            Transaction::read(0x70, vec![0xbe, 0xef, 0x92]),
+            Transaction::write(0x70, vec![0x60, 0x9c]),
+            Transaction::read(0x70, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay::default();
+        i2c::execute_timed(
+            &mut mock,
+            0x70,
+            &ShtCommand::MeasureNormal,
+            &mut delay,
+            &mut buf,
+        )
+        .unwrap();
+        i2c::execute_timed(
+            &mut mock,
+            0x70,
+            &ShtCommand::MeasureLowPower,
+            &mut delay,
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(delay.sleeps_us, [12_100, 800]);
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_with_crc_transform() {
+        let mut buf = [0; 3];
+        let xor = |word: &mut [u8; 2]| {
+            word[0] ^= 0xff;
+            word[1] ^= 0xff;
+        };
+
+        // CRC is valid for the decoded word
+        let expectations = [Transaction::read(0x58, vec![0x41, 0x10, 0x92])];
+        let mut mock = I2cMock::new(&expectations);
+        i2c::read_words_with_crc_transform(&mut mock, 0x58, &mut buf, xor).unwrap();
+        assert_eq!(buf, [0xbe, 0xef, 0x92]);
+        mock.done();
+
+        // CRC is valid for the wire bytes only
+        let expectations = [Transaction::read(0x58, vec![0xbe, 0xef, 0x92])];
+        let mut mock = I2cMock::new(&expectations);
+        match i2c::read_words_with_crc_transform(&mut mock, 0x58, &mut buf, xor) {
+            Err(i2c::Error::Crc) => {}
+            Err(_) => panic!("Invalid error: Must be Crc"),
+            Ok(_) => panic!("CRC check did not fail"),
+        }
+        mock.done();
+    }
+
+    #[test]
+    fn read_spec_execute_read() {
+        let spec = i2c::ReadSpec {
+            command: 0x7866,
+            words: 2,
+            delay_us: 12_100,
+        };
+        let mut buf = [0; 6];
+
+        let expectations = [
+            Transaction::write(0x70, vec![0x78, 0x66]),
+            Transaction::read(0x70, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay::default();
+        spec.execute_read(&mut mock, 0x70, &mut delay, &mut buf)
+            .unwrap();
+        assert_eq!(buf, [0x12, 0x34, 0x37, 0x56, 0x78, 0x7d]);
+        assert_eq!(delay.sleeps_us, [12_100]);
+        mock.done();
+
+        let mut mock = I2cMock::new(&[]);
+        match spec.execute_read(&mut mock, 0x70, &mut delay, &mut buf[..3]) {
+            Err(i2c::Error::InvalidBufferSize) => {}
+            _ => panic!("Invalid result: Must be InvalidBufferSize"),
+        }
+        mock.done();
+
+        let huge = i2c::ReadSpec {
+            words: usize::MAX,
+            ..spec
+        };
+        let mut mock = I2cMock::new(&[]);
+        match huge.execute_read(&mut mock, 0x70, &mut delay, &mut buf) {
+            Err(i2c::Error::InvalidBufferSize) => {}
+            _ => panic!("Invalid result: Must be InvalidBufferSize"),
+        }
+        mock.done();
+    }
+
+    /// Bus which answers every read with the given bytes, even if fewer were requested.
+    #[derive(Debug, PartialEq)]
+    struct CountedMock {
+        response: Vec<u8>,
+    }
+
+    impl embedded_hal::i2c::ErrorType for CountedMock {
+        type Error = ErrorKind;
+    }
+
+    impl i2c::CountedRead for CountedMock {
+        fn read_counted(&mut self, _address: u8, read: &mut [u8]) -> Result<usize, ErrorKind> {
+            let len = self.response.len().min(read.len());
+            read[..len].copy_from_slice(&self.response[..len]);
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn read_exact_words_with_crc() {
+        let mut buf = [0; 6];
+
+        // Complete read
+        let mut bus = CountedMock {
+            response: vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d],
+        };
+        assert_eq!(
+            i2c::read_exact_words_with_crc(&mut bus, 0x58, &mut buf),
+            Ok(())
+        );
+        assert_eq!(buf, [0x12, 0x34, 0x37, 0x56, 0x78, 0x7d]);
+
+        // Short read
+        let mut bus = CountedMock {
+            response: vec![0xbe, 0xef, 0x92],
+        };
+        assert_eq!(
+            i2c::read_exact_words_with_crc(&mut bus, 0x58, &mut buf),
+            Err(i2c::Error::ShortRead)
+        );
+    }
+
+    #[test]
+    fn read_words_with_ready_bit() {
+        let mut buf = [0; 6];
+
+        let expectations = [
+            Transaction::read(0x58, vec![0x80, 0x00, 0xa2, 0x12, 0x34, 0x37]),
+            Transaction::read(0x58, vec![0x00, 0x00, 0x81, 0x12, 0x34, 0x37]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        assert!(i2c::read_words_with_ready_bit(&mut mock, 0x58, &mut buf, 0x8000).unwrap());
+        assert!(!i2c::read_words_with_ready_bit(&mut mock, 0x58, &mut buf, 0x8000).unwrap());
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_for_each() {
+        let mut buf = [0; 6];
+        let mut sum = 0;
+        let mut indices = Vec::new();
+
+        let expectations = [Transaction::read(
+            0x58,
+            vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d],
+        )];
+        let mut mock = I2cMock::new(&expectations);
+        i2c::read_words_for_each(&mut mock, 0x58, &mut buf, |index, word| {
+            indices.push(index);
+            sum += u32::from(word);
+        })
+        .unwrap();
+        assert_eq!(sum, 0x1234 + 0x5678);
+        assert_eq!(indices, [0, 1]);
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_with_crc_timed() {
+        let mut buf = [0; 3];
+        let mut ticks = 100;
+        let now = || {
+            ticks += 7;
+            ticks
+        };
+
+        let expectations = [Transaction::read(0x58, vec![0xbe, 0xef, 0x92])];
+        let mut mock = I2cMock::new(&expectations);
+        let elapsed = i2c::read_words_with_crc_timed(&mut mock, 0x58, &mut buf, now).unwrap();
+        assert_eq!(elapsed, 7);
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_with_crc_stats() {
+        let mut buf = [0; 3];
+        let mut stats = i2c::CrcStats::default();
+
+        let expectations = [
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92]).with_error(ErrorKind::Other),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        for _ in 0..expectations.len() {
+            let _ = i2c::read_words_with_crc_stats(&mut mock, 0x58, &mut buf, &mut stats);
+        }
+        assert_eq!(
+            stats,
+            i2c::CrcStats {
+                total: 3,
+                errors: 1
+            }
+        );
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_with_crc_retry_fixed() {
+        let mut buf = [0; 3];
+        let policy = i2c::RetryPolicy {
+            max_attempts: 3,
+            delay_us: 100,
+            backoff: i2c::Backoff::Fixed,
+        };
+
+        let expectations = [
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]).with_error(ErrorKind::Other),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay::default();
+        i2c::read_words_with_crc_retry(&mut mock, 0x58, &mut buf, &mut delay, &policy).unwrap();
+        assert_eq!(buf, [0xbe, 0xef, 0x92]);
+        assert_eq!(delay.sleeps_us, [100, 100]);
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_with_crc_retry_exponential() {
+        let mut buf = [0; 3];
+        let policy = i2c::RetryPolicy {
+            max_attempts: 4,
+            delay_us: 100,
+            backoff: i2c::Backoff::Exponential,
+        };
+
+        let expectations = [
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay::default();
+        match i2c::read_words_with_crc_retry(&mut mock, 0x58, &mut buf, &mut delay, &policy) {
+            Err(i2c::Error::Crc) => {}
+            Err(_) => panic!("Invalid error: Must be Crc"),
+            Ok(_) => panic!("CRC check did not fail"),
+        }
+        assert_eq!(delay.sleeps_us, [100, 200, 400]);
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_with_crc_retry_if() {
+        let mut buf = [0; 3];
+        let policy = i2c::RetryPolicy {
+            max_attempts: 5,
+            delay_us: 100,
+            backoff: i2c::Backoff::Fixed,
+        };
+        let should_retry = |err: &ErrorKind| *err == ErrorKind::Bus;
+
+        let expectations = [
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]).with_error(ErrorKind::Bus),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]).with_error(ErrorKind::Other),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay::default();
+        match i2c::read_words_with_crc_retry_if(
+            &mut mock,
+            0x58,
+            &mut buf,
+            &mut delay,
+            &policy,
+            should_retry,
+        ) {
+            Err(i2c::Error::I2cRead(ErrorKind::Other)) => {}
+            Err(_) => panic!("Invalid error: Must be I2cRead(Other)"),
+            Ok(_) => panic!("Read did not fail"),
+        }
+        assert_eq!(delay.sleeps_us, [100, 100]);
+        mock.done();
+    }
+
+    #[test]
+    fn decode_i16_be() {
+        assert_eq!(i2c::decode_i16_be(&[0xff, 0xfe]), -2);
+        assert_eq!(i2c::decode_i16_be(&[0x12, 0x34]), 0x1234);
+    }
+
+    #[test]
+    fn read_i16_words() {
+        let expectations = [Transaction::read(
+            0x58,
+            vec![0xff, 0xfe, 0x9d, 0x12, 0x34, 0x37],
+        )];
+        let mut mock = I2cMock::new(&expectations);
+        let words = i2c::read_i16_words::<2, _>(&mut mock, 0x58).unwrap();
+        assert_eq!(words, [-2, 0x1234]);
+        mock.done();
+    }
+
+    #[test]
+    fn read_scaled() {
+        // SHTC3 conversion formulas for temperature and relative humidity
+        let scales = [(-45.0, 175.0), (0.0, 100.0)];
+
+        let expectations = [Transaction::read(
+            0x70,
+            vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xa2],
+        )];
+        let mut mock = I2cMock::new(&expectations);
+        let [temperature, humidity] = i2c::read_scaled(&mut mock, 0x70, scales).unwrap();
+        assert!((temperature - 25.0).abs() < 0.001);
+        assert!((humidity - 50.0).abs() < 0.001);
+        mock.done();
+    }
+
+    #[test]
+    fn read_triplet() {
+        // Valid CRC
+        {
+            let expectations = [Transaction::read(
+                0x62,
+                vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d, 0xbe, 0xef, 0x92],
+            )];
+            let mut mock = I2cMock::new(&expectations);
+            assert_eq!(
+                i2c::read_triplet(&mut mock, 0x62).unwrap(),
+                (0x1234, 0x5678, 0xbeef)
+            );
+            mock.done();
+        }
+
+        // Invalid CRC in the second word
+        {
+            let expectations = [Transaction::read(
+                0x62,
+                vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x00, 0xbe, 0xef, 0x92],
+            )];
+            let mut mock = I2cMock::new(&expectations);
+            match i2c::read_triplet(&mut mock, 0x62) {
+                Err(i2c::Error::Crc) => {}
+                Err(_) => panic!("Invalid error: Must be Crc"),
+                Ok(_) => panic!("CRC check did not fail"),
+            }
+            mock.done();
+        }
+    }
+
+    #[test]
+    fn decode_u32_be() {
+        assert_eq!(i2c::decode_u32_be(&[0x1234, 0x5678]), 0x12345678);
+    }
+
+    #[test]
+    fn read_u32() {
+        let expectations = [Transaction::read(
+            0x58,
+            vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d],
+        )];
+        let mut mock = I2cMock::new(&expectations);
+        assert_eq!(i2c::read_u32(&mut mock, 0x58).unwrap(), 0x12345678);
+        mock.done();
+    }
+
+    #[test]
+    fn read_f32_array() {
+        // Valid CRC
+        {
+            let expectations = [Transaction::read(
+                0x69,
+                vec![
+                    0x3f, 0x80, 0xd0, 0x00, 0x00, 0x81, 0x40, 0x20, 0x8e, 0x00, 0x00, 0x81,
+                ],
+            )];
+            let mut mock = I2cMock::new(&expectations);
+            assert_eq!(
+                i2c::read_f32_array::<2, _>(&mut mock, 0x69).unwrap(),
+                [1.0, 2.5]
+            );
+            mock.done();
+        }
+
+        // Invalid CRC
+        {
+            let expectations = [Transaction::read(
+                0x69,
+                vec![
+                    0x3f, 0x80, 0xd0, 0x00, 0x00, 0x81, 0x40, 0x20, 0x8e, 0x00, 0x00, 0x00,
+                ],
+            )];
+            let mut mock = I2cMock::new(&expectations);
+            match i2c::read_f32_array::<2, _>(&mut mock, 0x69) {
+                Err(i2c::Error::Crc) => {}
+                Err(_) => panic!("Invalid error: Must be Crc"),
+                Ok(_) => panic!("CRC check did not fail"),
+            }
+            mock.done();
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Measurement {
+        temperature: u16,
+        humidity: u16,
+    }
+
+    impl i2c::FromWords for Measurement {
+        const WORDS: usize = 2;
+
+        fn from_words(words: &[u16]) -> Self {
+            Measurement {
+                temperature: words[0],
+                humidity: words[1],
+            }
+        }
+    }
+
+    #[test]
+    fn read_struct() {
+        // Valid CRC
+        {
+            let expectations = [Transaction::read(
+                0x58,
+                vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d],
+            )];
+            let mut mock = I2cMock::new(&expectations);
+            let measurement: Measurement = i2c::read_struct(&mut mock, 0x58).unwrap();
+            assert_eq!(
+                measurement,
+                Measurement {
+                    temperature: 0x1234,
+                    humidity: 0x5678,
+                }
+            );
+            mock.done();
+        }
+
+        // Invalid CRC
+        {
+            let expectations = [Transaction::read(
+                0x58,
+                vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x00],
+            )];
+            let mut mock = I2cMock::new(&expectations);
+            match i2c::read_struct::<Measurement, _>(&mut mock, 0x58) {
+                Err(i2c::Error::Crc) => {}
+                Err(_) => panic!("Invalid error: Must be Crc"),
+                Ok(_) => panic!("CRC check did not fail"),
+            }
+            mock.done();
+        }
+    }
+
+    #[test]
+    fn read_data_and_status() {
+        let expectations = [Transaction::read(
+            0x69,
+            vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d, 0x00, 0x01, 0xb0],
+        )];
+        let mut mock = I2cMock::new(&expectations);
+        let (data, status) = i2c::read_data_and_status::<2, _>(&mut mock, 0x69).unwrap();
+        assert_eq!(data, [0x1234, 0x5678]);
+        assert_eq!(status, 0x0001);
+        mock.done();
+    }
+
+    #[test]
+    fn read_n_words() {
+        // Valid CRC
+        {
+            let expectations = [Transaction::read(
+                0x58,
+                vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d, 0xbe, 0xef, 0x92],
+            )];
+            let mut mock = I2cMock::new(&expectations);
+            let words = i2c::read_n_words::<3, _>(&mut mock, 0x58).unwrap();
+            assert_eq!(words, [0x1234, 0x5678, 0xbeef]);
+            mock.done();
+        }
+
+        // Invalid CRC
+        {
+            let expectations = [Transaction::read(
+                0x58,
+                vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d, 0xbe, 0xef, 0x00],
+            )];
+            let mut mock = I2cMock::new(&expectations);
+            match i2c::read_n_words::<3, _>(&mut mock, 0x58) {
+                Err(i2c::Error::Crc) => {}
+                Err(_) => panic!("Invalid error: Must be Crc"),
+                Ok(_) => panic!("CRC check did not fail"),
+            }
+            mock.done();
+        }
+    }
+
+    #[test]
+    fn read_length_prefixed() {
+        let mut scratch = [0; 9];
+
+        let expectations = [
+            Transaction::write(0x58, vec![0xab, 0xcd]),
+            Transaction::read(0x58, vec![0x00, 0x02, 0xe3]),
+            Transaction::read(0x58, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let data = i2c::read_length_prefixed(&mut mock, 0x58, 0xabcd, &mut scratch).unwrap();
+        assert_eq!(data, [0x12, 0x34, 0x37, 0x56, 0x78, 0x7d]);
+        mock.done();
+    }
+
+    #[test]
+    fn read_length_prefixed_too_long() {
+        let mut scratch = [0; 3];
+
+        let expectations = [
+            Transaction::write(0x58, vec![0xab, 0xcd]),
+            Transaction::read(0x58, vec![0x00, 0x02, 0xe3]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        match i2c::read_length_prefixed(&mut mock, 0x58, 0xabcd, &mut scratch) {
+            Err(i2c::Error::InvalidBufferSize) => {}
+            Err(_) => panic!("Invalid error: Must be InvalidBufferSize"),
+            Ok(_) => panic!("Length check did not fail"),
+        }
+        mock.done();
+    }
+
+    #[test]
+    fn read_length_prefixed_huge_length() {
+        let mut scratch = [0; 9];
+
+        for length in [[0x55, 0x56, 0xc9], [0xff, 0xff, 0xac]] {
+            let expectations = [
+                Transaction::write(0x58, vec![0xab, 0xcd]),
+                Transaction::read(0x58, length.to_vec()),
+            ];
+            let mut mock = I2cMock::new(&expectations);
+            match i2c::read_length_prefixed(&mut mock, 0x58, 0xabcd, &mut scratch) {
+                Err(i2c::Error::InvalidBufferSize) => {}
+                Err(_) => panic!("Invalid error: Must be InvalidBufferSize"),
+                Ok(_) => panic!("Length check did not fail"),
+            }
+            mock.done();
+        }
+    }
+
+    #[test]
+    fn frames_data_eq() {
+        // Same data, different CRC
+        assert!(i2c::frames_data_eq(
+            &[0xbe, 0xef, 0x92, 0x12, 0x34, 0x37],
+            &[0xbe, 0xef, 0x00, 0x12, 0x34, 0x00]
+        ));
+
+        // Same CRC, different data
+        assert!(!i2c::frames_data_eq(
+            &[0xbe, 0xef, 0x92, 0x12, 0x34, 0x37],
+            &[0xbe, 0xef, 0x92, 0x12, 0x35, 0x37]
+        ));
+
+        // Different length
+        assert!(!i2c::frames_data_eq(
+            &[0xbe, 0xef, 0x92],
+            &[0xbe, 0xef, 0x92, 0x12, 0x34, 0x37]
+        ));
+    }
+
+    #[test]
+    fn read_words_with_crc_empty() {
+        let mut mock = I2cMock::new(&[]);
+        i2c::read_words_with_crc(&mut mock, 0x58, &mut []).unwrap();
+        mock.done();
+    }
+
+    #[test]
+    fn write_command_with_bytes_empty() {
+        let expectations = [Transaction::write(0x58, vec![0xab, 0xcd])];
+        let mut mock = I2cMock::new(&expectations);
+        i2c::write_command_with_bytes::<2, _>(&mut mock, 0x58, 0xabcd, &[]).unwrap();
+        mock.done();
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn write_command() {
+        let expectations = [Transaction::write(0x58, vec![0xab, 0xcd])];
+        let mut mock = I2cMock::new(&expectations);
+
+        i2c::write_command(&mut mock, 0x58, 0xabcd).unwrap();
+
+        mock.done();
+    }
+
+    #[test]
+    fn write_command_u8() {
+        let expectations = [Transaction::write(0x58, vec![0xab])];
+        let mut mock = I2cMock::new(&expectations);
+
+        i2c::write_command_u8(&mut mock, 0x58, 0xab).unwrap();
+
+        mock.done();
+    }
+
+    #[test]
+    fn command_frame_len() {
+        assert_eq!(i2c::command_frame_len(0), 2);
+        assert_eq!(i2c::command_frame_len(2), 8);
+    }
+
+    #[test]
+    fn write_command_with_bytes() {
+        let expectations = [Transaction::write(
+            0x58,
+            vec![0xab, 0xcd, 0xbe, 0xef, 0x92, 0x12, 0x34, 0x37],
+        )];
+        let mut mock = I2cMock::new(&expectations);
+
+        i2c::write_command_with_bytes::<{ i2c::command_frame_len(2) }, _>(
+            &mut mock,
+            0x58,
+            0xabcd,
+            &[0xbe, 0xef, 0x12, 0x34],
+        )
+        .unwrap();
+
+        mock.done();
+    }
+
+    #[test]
+    fn write_command_with_bytes_invalid_size() {
+        let mut mock = I2cMock::new(&[]);
+
+        // Odd payload length
+        match i2c::write_command_with_bytes::<8, _>(&mut mock, 0x58, 0xabcd, &[0xbe, 0xef, 0x12]) {
+            Err(i2c::Error::InvalidBufferSize) => {}
+            _ => panic!("Invalid result: Must be InvalidBufferSize"),
+        }
+
+        // Frame does not fit into the buffer
+        match i2c::write_command_with_bytes::<7, _>(
+            &mut mock,
+            0x58,
+            0xabcd,
+            &[0xbe, 0xef, 0x12, 0x34],
+        ) {
+            Err(i2c::Error::InvalidBufferSize) => {}
+            _ => panic!("Invalid result: Must be InvalidBufferSize"),
+        }
+
+        mock.done();
+    }
+
+    #[test]
+    fn with_pre_op() {
+        // The hook selects a channel on a bus multiplexer before every transaction
+        let expectations = [
+            Transaction::write(0x70, vec![0x01]),
+            Transaction::write(0x58, vec![0xab, 0xcd]),
+            Transaction::write(0x70, vec![0x01]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mock = I2cMock::new(&expectations);
+        let mut calls = 0;
+        let mut bus = i2c::WithPreOp::new(mock, |mock: &mut I2cMock| {
+            calls += 1;
+            embedded_hal::i2c::I2c::write(mock, 0x70, &[0x01]).unwrap();
+        });
+
+        let mut buf = [0; 3];
+        i2c::write_command_u16(&mut bus, 0x58, 0xabcd).unwrap();
+        assert!(i2c::read_words_with_crc(&mut bus, 0x58, &mut buf).is_ok());
+
+        bus.release().done();
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn write_command_u16_retry() {
+        let policy = i2c::RetryPolicy {
+            max_attempts: 3,
+            delay_us: 50,
+            backoff: i2c::Backoff::Fixed,
+        };
+
+        let expectations = [
+            Transaction::write(0x58, vec![0xab, 0xcd]).with_error(ErrorKind::ArbitrationLoss),
+            Transaction::write(0x58, vec![0xab, 0xcd]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay::default();
+        i2c::write_command_u16_retry(&mut mock, 0x58, 0xabcd, &mut delay, &policy).unwrap();
+        assert_eq!(delay.sleeps_us, [50]);
+        mock.done();
+    }
+
+    #[test]
+    fn wakeup() {
+        use embedded_hal::i2c::NoAcknowledgeSource;
+
+        // Sensor does not acknowledge
+        let expectations = [Transaction::write(0x70, vec![0x35, 0x17])
+            .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))];
+        let mut mock = I2cMock::new(&expectations);
+        i2c::wakeup(&mut mock, 0x70, 0x3517).unwrap();
+        mock.done();
+
+        // Other errors are returned
+        let expectations = [Transaction::write(0x70, vec![0x35, 0x17]).with_error(ErrorKind::Bus)];
+        let mut mock = I2cMock::new(&expectations);
+        assert_eq!(i2c::wakeup(&mut mock, 0x70, 0x3517), Err(ErrorKind::Bus));
+        mock.done();
+    }
+
+    #[test]
+    fn write_command_u16() {
+        let expectations = [Transaction::write(0x58, vec![0xab, 0xcd])];
+        let mut mock = I2cMock::new(&expectations);
+
+        i2c::write_command_u16(&mut mock, 0x58, 0xabcd).unwrap();
+
+        mock.done();
+    }
+
+    #[test]
+    fn fmt_frame() {
+        let mut out = [0; 8];
+        assert_eq!(i2c::fmt_frame(&[0xbe, 0xef, 0x92], &mut out), Ok(8));
+        assert_eq!(&out, b"be ef 92");
+
+        assert_eq!(i2c::fmt_frame(&[], &mut out), Ok(0));
+        assert!(i2c::fmt_frame(&[0xbe, 0xef, 0x92], &mut out[..7]).is_err());
+    }
+
+    #[test]
+    fn extract_channel() {
+        let frame = [
+            0x12, 0x34, 0x37, 0x56, 0x78, 0x7d, 0xbe, 0xef, 0x92, 0x00, 0x00, 0x81,
+        ];
+        let mut channel_b = [0; 2];
+        i2c::extract_channel(&frame, 1, 2, &mut channel_b).unwrap();
+        assert_eq!(channel_b, [0x5678, 0x0000]);
+
+        let mut corrupted = frame;
+        corrupted[2] = 0x00;
+        assert_eq!(
+            i2c::extract_channel(&corrupted, 1, 2, &mut channel_b),
+            Err(crate::crc8::Error::CrcError)
+        );
+        assert_eq!(
+            i2c::extract_channel(&frame, 1, 2, &mut [0; 3]),
+            Err(crate::crc8::Error::InvalidBufferSize)
+        );
+
+        let mut channel_a = [0; 1];
+        i2c::extract_channel(&frame, 0, usize::MAX, &mut channel_a).unwrap();
+        assert_eq!(channel_a, [0x1234]);
+        i2c::extract_channel(&frame, 5, 6, &mut []).unwrap();
+    }
+
+    #[test]
+    fn verify_device() {
+        let expectations = [
+            Transaction::write(0x62, vec![0x36, 0x82]),
+            Transaction::read(0x62, vec![0x12, 0x34, 0x37]),
+            Transaction::write(0x62, vec![0x36, 0x82]),
+            Transaction::read(0x62, vec![0x12, 0x34, 0x37]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        assert!(i2c::verify_device(&mut mock, 0x62, 0x3682, 0x1234).unwrap());
+        assert!(!i2c::verify_device(&mut mock, 0x62, 0x3682, 0x5678).unwrap());
+        mock.done();
+    }
+
+    #[test]
+    fn read_after_pointer() {
+        let expectations = [
+            Transaction::write(0x44, vec![0x03]),
+            Transaction::read(0x44, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut buf = [0; 3];
+        i2c::read_after_pointer(&mut mock, 0x44, 0x03, &mut buf).unwrap();
+        assert_eq!(buf, [0xbe, 0xef, 0x92]);
+        mock.done();
+    }
+
+    #[test]
+    fn run_init_script() {
+        const SCRIPT: &[(u16, u32)] = &[(0x3f86, 500_000), (0x21b1, 0)];
+        let expectations = [
+            Transaction::write(0x62, vec![0x3f, 0x86]),
+            Transaction::write(0x62, vec![0x21, 0xb1]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay { sleeps_us: vec![] };
+        i2c::run_init_script(&mut mock, 0x62, SCRIPT, &mut delay).unwrap();
+        assert_eq!(delay.sleeps_us, [500_000, 0]);
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_with_crc_sanity() {
+        let expectations = [
+            Transaction::read(0x58, vec![0x00, 0x00, 0x81, 0x00, 0x00, 0x81]),
+            Transaction::read(0x58, vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+            Transaction::read(0x58, vec![0x00, 0x00, 0x81, 0xff, 0xff, 0xac]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut buf = [0; 6];
+        for _ in 0..2 {
+            match i2c::read_words_with_crc_sanity(&mut mock, 0x58, &mut buf) {
+                Err(i2c::Error::SuspiciousFrame) => {}
+                Err(_) => panic!("Invalid error: Must be SuspiciousFrame"),
+                Ok(_) => panic!("Sanity check did not fail"),
+            }
+        }
+        i2c::read_words_with_crc_sanity(&mut mock, 0x58, &mut buf).unwrap();
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_with_crc_at() {
+        let expectations = [
+            Transaction::write(0x40, vec![0xe0, 0x00]),
+            Transaction::read(0x41, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut buf = [0; 3];
+        i2c::read_words_with_crc_at(&mut mock, 0x40, 0x41, 0xe000, &mut buf).unwrap();
+        assert_eq!(buf, [0xbe, 0xef, 0x92]);
+        mock.done();
+    }
+
+    #[test]
+    fn read_and_validate_streaming() {
+        let expectations = [
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92]),
+            Transaction::read(0x58, vec![0x12, 0x34, 0x00]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut buf = [0; 9];
+        match i2c::read_and_validate_streaming(&mut mock, 0x58, &mut buf, 3) {
+            Err((1, i2c::Error::Crc)) => {}
+            Err(_) => panic!("Invalid error: Must be Crc in word 1"),
+            Ok(_) => panic!("CRC check did not fail"),
+        }
+        mock.done();
+
+        // Bad word in the middle of the second chunk of two words
+        let expectations = [
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92, 0x12, 0x34, 0x37]),
+            Transaction::read(0x58, vec![0x56, 0x78, 0x7d, 0x00, 0x00, 0x00]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut buf = [0; 18];
+        match i2c::read_and_validate_streaming(&mut mock, 0x58, &mut buf, 6) {
+            Err((3, i2c::Error::Crc)) => {}
+            Err(_) => panic!("Invalid error: Must be Crc in word 3"),
+            Ok(_) => panic!("CRC check did not fail"),
+        }
+        mock.done();
+    }
+
+    #[test]
+    fn read_th() {
+        const SHTC3: i2c::Calibration = i2c::Calibration {
+            t_offset: -45.0,
+            t_span: 175.0,
+            rh_offset: 0.0,
+            rh_span: 100.0,
+        };
+        let expectations = [Transaction::read(
+            0x70,
+            vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xa2],
+        )];
+        let mut mock = I2cMock::new(&expectations);
+        let (t, rh) = i2c::read_th(&mut mock, 0x70, &SHTC3).unwrap();
+        assert!((t - 25.0).abs() < 0.01);
+        assert!((rh - 50.0).abs() < 0.01);
+        mock.done();
+    }
+
+    #[test]
+    fn read_validated() {
+        let expectations = [
+            Transaction::read(0x58, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7d]),
+            Transaction::read(0x58, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x00]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let words = i2c::read_validated::<2, _>(&mut mock, 0x58).unwrap();
+        assert_eq!(words.into_inner(), [0x1234, 0x5678]);
+        match i2c::read_validated::<2, _>(&mut mock, 0x58) {
+            Err(i2c::Error::Crc) => {}
+            Err(_) => panic!("Invalid error: Must be Crc"),
+            Ok(_) => panic!("CRC check did not fail"),
+        }
+        mock.done();
+    }
+
+    #[test]
+    fn read_words_tolerant() {
+        let expectations = [
+            Transaction::read(
+                0x58,
+                vec![0xbe, 0xef, 0x92, 0x12, 0x34, 0x00, 0x00, 0x00, 0x81],
+            ),
+            Transaction::read(
+                0x58,
+                vec![0xbe, 0xef, 0x00, 0x12, 0x34, 0x00, 0x00, 0x00, 0x81],
+            ),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut buf = [0; 9];
+        let mut bad = [0; 1];
+        assert_eq!(
+            i2c::read_words_tolerant(&mut mock, 0x58, &mut buf, 1, &mut bad).unwrap(),
+            1
+        );
+        assert_eq!(bad, [1]);
+        match i2c::read_words_tolerant(&mut mock, 0x58, &mut buf, 1, &mut bad) {
+            Err(i2c::Error::Crc) => {}
+            Err(_) => panic!("Invalid error: Must be Crc"),
+            Ok(_) => panic!("CRC check did not fail"),
+        }
+        mock.done();
+    }
+
+    #[test]
+    fn read_config_bool() {
+        let expectations = [
+            Transaction::write(0x62, vec![0x23, 0x13]),
+            Transaction::read(0x62, vec![0x00, 0x01, 0xb0]),
+            Transaction::write(0x62, vec![0x23, 0x13]),
+            Transaction::read(0x62, vec![0x00, 0x00, 0x81]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        assert!(i2c::read_config_bool(&mut mock, 0x62, 0x2313).unwrap());
+        assert!(!i2c::read_config_bool(&mut mock, 0x62, 0x2313).unwrap());
+        mock.done();
+    }
+
+    #[test]
+    fn write_config_bool() {
+        let expectations = [
+            Transaction::write(0x62, vec![0x24, 0x16, 0x00, 0x01, 0xb0]),
+            Transaction::write(0x62, vec![0x24, 0x16, 0x00, 0x00, 0x81]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        i2c::write_config_bool(&mut mock, 0x62, 0x2416, true).unwrap();
+        i2c::write_config_bool(&mut mock, 0x62, 0x2416, false).unwrap();
+        mock.done();
+    }
+
+    #[test]
+    fn read_continuous() {
+        let expectations = [
+            Transaction::read(0x62, vec![0xbe, 0xef, 0x92]),
+            Transaction::read(0x62, vec![0x12, 0x34, 0x37]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay { sleeps_us: vec![] };
+        let mut buf = [0; 3];
+        let mut frames = vec![];
+        i2c::read_continuous(&mut mock, 0x62, &mut buf, &mut delay, 5_000_000, |frame| {
+            frames.push(frame.to_vec());
+            frames.len() < 2
+        })
+        .unwrap();
+        assert_eq!(frames, [[0xbe, 0xef, 0x92], [0x12, 0x34, 0x37]]);
+        assert_eq!(delay.sleeps_us, [5_000_000]);
+        mock.done();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn diff_words() {
+        assert_eq!(
+            i2c::diff_words(&[0x1234, 0x5678, 0xbeef], &[0x1234, 0x5679, 0x0000]),
+            [(1, 0x5678, 0x5679), (2, 0xbeef, 0x0000)]
+        );
+        assert!(i2c::diff_words(&[0x1234], &[0x1234]).is_empty());
+    }
+
+    #[test]
+    fn read_words_le() {
+        let expectations = [Transaction::read(
+            0x58,
+            vec![0xbe, 0xef, 0x92, 0x12, 0x34, 0x37],
+        )];
+        let mut mock = I2cMock::new(&expectations);
+        let words = i2c::read_words_le::<2, _>(&mut mock, 0x58).unwrap();
+        assert_eq!(words, [0xefbe, 0x3412]);
+        mock.done();
+    }
+
+    #[test]
+    fn write_command_u16_crc() {
+        let expectations = [
+            Transaction::write(0x58, vec![0xbe, 0xef, 0x92]),
+            Transaction::write(0x58, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        i2c::write_command_u16_crc(&mut mock, 0x58, 0xbeef).unwrap();
+        i2c::Command(0xbeef)
+            .write_with_crc(&mut mock, 0x58)
+            .unwrap();
+        mock.done();
+    }
+
+    #[test]
+    fn command_and_data_word() {
+        let expectations = [
+            Transaction::write(0x62, vec![0x21, 0xb1]),
+            Transaction::write(0x62, vec![0x21, 0xb1]),
+            Transaction::write(0x62, vec![0x24, 0x16, 0x00, 0x01, 0xb0]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let start = i2c::Command(0x21b1);
+        start.write(&mut mock, 0x62).unwrap();
+        i2c::write_command_u16(&mut mock, 0x62, start.into()).unwrap();
+        let word = i2c::DataWord::from(0x0001);
+        i2c::write_command_with_bytes::<5, _>(&mut mock, 0x62, 0x2416, &word.0.to_be_bytes())
+            .unwrap();
+        mock.done();
+
+        assert_eq!(i2c::Command::from(0x21b1), start);
+        assert_eq!(u16::from(start), 0x21b1);
+        assert_eq!(word.with_crc(), [0x00, 0x01, 0xb0]);
+    }
+
+    crate::define_measurement! {
+        #[derive(Debug, PartialEq)]
+        struct Scd4xMeasurement {
+            co2: u16,
+            temperature: i16,
+            humidity: u16,
+        }
+        command = 0xec05;
+        delay_us = 1_000;
+    }
+
+    #[test]
+    fn define_measurement() {
+        use i2c::FromWords;
+
+        assert_eq!(Scd4xMeasurement::WORDS, 3);
+        let expectations = [
+            Transaction::write(0x62, vec![0xec, 0x05]),
+            Transaction::read(
+                0x62,
+                vec![0x12, 0x34, 0x37, 0xff, 0xfe, 0x9d, 0x80, 0x00, 0xa2],
+            ),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay { sleeps_us: vec![] };
+        let measurement = Scd4xMeasurement::read(&mut mock, 0x62, &mut delay).unwrap();
+        assert_eq!(
+            measurement,
+            Scd4xMeasurement {
+                co2: 0x1234,
+                temperature: -2,
+                humidity: 0x8000,
+            }
+        );
+        assert_eq!(delay.sleeps_us, [1_000]);
+        mock.done();
+    }
+
+    #[test]
+    fn query_words() {
+        let expectations = [
+            Transaction::write_read(
+                0x44,
+                vec![0x24, 0x00],
+                vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xa2],
+            ),
+            Transaction::write_read(
+                0x44,
+                vec![0x24, 0x00],
+                vec![0x66, 0x66, 0x93, 0x80, 0x00, 0x00],
+            ),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let words = i2c::query_words::<2, _>(&mut mock, 0x44, 0x2400).unwrap();
+        assert_eq!(words, [0x6666, 0x8000]);
+        match i2c::query_words::<2, _>(&mut mock, 0x44, 0x2400) {
+            Err(i2c::Error::Crc) => {}
+            Err(_) => panic!("Invalid error: Must be Crc"),
+            Ok(_) => panic!("CRC check did not fail"),
+        }
+        mock.done();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn error_is_std_error() {
+        let error: Box<dyn std::error::Error> = Box::new(i2c::Error::<I2cMock>::Crc);
+        assert_eq!(error.to_string(), "CRC validation failed");
+    }
+
+    #[test]
+    fn poll_until_ready() {
+        let policy = i2c::RetryPolicy {
+            max_attempts: 2,
+            delay_us: 100,
+            backoff: i2c::Backoff::Fixed,
+        };
+
+        // Ready on the second poll
+        let expectations = [
+            Transaction::write(0x62, vec![0xe4, 0xb8]),
+            Transaction::read(0x62, vec![0x80, 0x00, 0xa2]),
+            Transaction::write(0x62, vec![0xe4, 0xb8]),
+            Transaction::read(0x62, vec![0x00, 0x01, 0xb0]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay::default();
+        assert!(
+            i2c::poll_until_ready(&mut mock, 0x62, 0xe4b8, 0x07ff, &mut delay, &policy).unwrap()
+        );
+        assert_eq!(delay.sleeps_us, [100]);
+        mock.done();
+
+        // Never ready
+        let expectations = [
+            Transaction::write(0x62, vec![0xe4, 0xb8]),
+            Transaction::read(0x62, vec![0x00, 0x00, 0x81]),
+            Transaction::write(0x62, vec![0xe4, 0xb8]),
+            Transaction::read(0x62, vec![0x00, 0x00, 0x81]),
+        ];
+        let mut mock = I2cMock::new(&expectations);
+        let mut delay = RecordingDelay::default();
+        assert!(
+            !i2c::poll_until_ready(&mut mock, 0x62, 0xe4b8, 0x07ff, &mut delay, &policy).unwrap()
+        );
+        assert_eq!(delay.sleeps_us, [100]);
         mock.done();
     }
 }
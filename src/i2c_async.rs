@@ -7,9 +7,10 @@
 //! [`embedded-hal-async`]: https://crates.io/crates/embedded-hal-async
 
 use crate::crc8;
+use embedded_hal_async::delay::DelayNs;
 use embedded_hal_async::i2c;
 
-pub use crate::i2c::Error;
+pub use crate::i2c::{Backoff, Error, RetryPolicy};
 
 /// Write an u8 command to the I²C bus.
 pub async fn write_command_u8<I: i2c::I2c>(
@@ -29,9 +30,20 @@ pub async fn write_command_u16<I: i2c::I2c>(
     i2c.write(addr, &command.to_be_bytes()).await
 }
 
+/// Write an u16 command followed by the CRC8 checksum of the command bytes to the I²C bus.
+pub async fn write_command_u16_crc<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+) -> Result<(), I::Error> {
+    let [hi, lo] = command.to_be_bytes();
+    i2c.write(addr, &[hi, lo, crc8::calculate(&[hi, lo])]).await
+}
+
 /// Read data into the provided buffer and validate the CRC8 checksum.
 ///
-/// If the checksum is wrong, return `Error::Crc`.
+/// If the checksum is wrong, return `Error::Crc`. An empty buffer returns `Ok(())` without
+/// accessing the bus, since some HALs reject zero-length reads.
 ///
 /// # Panics
 ///
@@ -46,15 +58,354 @@ pub async fn read_words_with_crc<I: i2c::I2c>(
         data.len() % 3 == 0,
         "Buffer must hold a multiple of 3 bytes"
     );
+    if data.is_empty() {
+        return Ok(());
+    }
     i2c.read(addr, data).await.map_err(Error::I2cRead)?;
     crc8::validate(data)?;
     Ok(())
 }
 
+/// Write an u16 command, wait for `wait_us` microseconds and read the response into the provided
+/// buffer, validating the CRC8 checksum.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub async fn measure<I: i2c::I2c, D: DelayNs>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+    delay: &mut D,
+    wait_us: u32,
+    data: &mut [u8],
+) -> Result<(), Error<I>> {
+    write_command_u16(i2c, addr, command)
+        .await
+        .map_err(Error::I2cWrite)?;
+    delay.delay_us(wait_us).await;
+    read_words_with_crc(i2c, addr, data).await
+}
+
+/// Read data into the provided buffer and validate the CRC8 checksum, retrying on failure.
+///
+/// Both bus errors and CRC errors are retried according to `policy`. If all attempts fail, the
+/// error of the last attempt is returned.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub async fn read_words_with_crc_retry<I: i2c::I2c, D: DelayNs>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    delay: &mut D,
+    policy: &RetryPolicy,
+) -> Result<(), Error<I>> {
+    let mut attempt = 1;
+    loop {
+        match read_words_with_crc(i2c, addr, data).await {
+            Ok(()) => return Ok(()),
+            Err(err) if attempt >= policy.max_attempts => return Err(err),
+            Err(_) => {
+                delay.delay_us(policy.retry_delay_us(attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Read data into the provided buffer, validate the CRC8 checksum and return whether the
+/// data-ready bit in the first word is set.
+///
+/// The first word is considered ready if any of the bits in `ready_mask` is set. Returns
+/// `Error::InvalidBufferSize` if the buffer does not hold at least one word.
+///
+/// # Panics
+///
+/// This method will consider every third byte a checksum byte. If the buffer size is not a
+/// multiple of 3, then it will panic.
+pub async fn read_words_with_ready_bit<I: i2c::I2c>(
+    i2c: &mut I,
+    addr: u8,
+    data: &mut [u8],
+    ready_mask: u16,
+) -> Result<bool, Error<I>> {
+    if data.len() < 3 {
+        return Err(Error::InvalidBufferSize);
+    }
+    read_words_with_crc(i2c, addr, data).await?;
+    Ok(u16::from_be_bytes([data[0], data[1]]) & ready_mask != 0)
+}
+
+/// Poll the data-ready status of a sensor until it reports ready data.
+///
+/// Every poll writes `command` and reads one status word, validating its CRC8 checksum. The data
+/// is considered ready if any of the bits in `ready_mask` is set (e.g. `0x07ff` for the SCD4x
+/// `get_data_ready_status` command). Between two polls, the delay of `policy` is awaited. Returns
+/// `Ok(true)` as soon as the sensor is ready and `Ok(false)` if it is still not ready after
+/// `policy.max_attempts` polls. Bus and CRC errors are returned immediately.
+pub async fn poll_until_ready<I: i2c::I2c, D: DelayNs>(
+    i2c: &mut I,
+    addr: u8,
+    command: u16,
+    ready_mask: u16,
+    delay: &mut D,
+    policy: &RetryPolicy,
+) -> Result<bool, Error<I>> {
+    let mut attempt = 1;
+    loop {
+        write_command_u16(i2c, addr, command)
+            .await
+            .map_err(Error::I2cWrite)?;
+        let mut status = [0; 3];
+        if read_words_with_ready_bit(i2c, addr, &mut status, ready_mask).await? {
+            return Ok(true);
+        }
+        if attempt >= policy.max_attempts {
+            return Ok(false);
+        }
+        delay.delay_us(policy.retry_delay_us(attempt)).await;
+        attempt += 1;
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    // TODO: `embedded-hal-mock`'s support for `embedded-hal-async` does not
-    // currently have a mock I2C implementation. When that's available, we
-    // should add tests for the async I2C functions here that are analogous to
-    // the ones in the `i2c` module.
+    use crate::i2c_async;
+
+    use core::future::Future;
+    use embedded_hal::i2c::{ErrorKind, I2c as _, Operation};
+    use embedded_hal_mock as hal;
+    use hal::eh1::i2c::{Mock as I2cMock, Transaction};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+
+    // `embedded-hal-mock` does not provide an async I2C mock yet, so the blocking mock is wrapped
+    // and the futures are driven by a minimal executor.
+
+    /// Async I2C mock which forwards to the blocking `embedded-hal-mock` implementation.
+    #[derive(Debug)]
+    struct AsyncI2cMock(I2cMock);
+
+    impl embedded_hal_async::i2c::ErrorType for AsyncI2cMock {
+        type Error = ErrorKind;
+    }
+
+    impl embedded_hal_async::i2c::I2c for AsyncI2cMock {
+        async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), ErrorKind> {
+            self.0.read(address, read)
+        }
+
+        async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), ErrorKind> {
+            self.0.write(address, write)
+        }
+
+        async fn write_read(
+            &mut self,
+            address: u8,
+            write: &[u8],
+            read: &mut [u8],
+        ) -> Result<(), ErrorKind> {
+            self.0.write_read(address, write, read)
+        }
+
+        async fn transaction(
+            &mut self,
+            address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), ErrorKind> {
+            self.0.transaction(address, operations)
+        }
+    }
+
+    /// Async delay which records all requested delays in microseconds.
+    #[derive(Default)]
+    struct RecordingDelay {
+        sleeps_us: Vec<u32>,
+    }
+
+    impl embedded_hal_async::delay::DelayNs for RecordingDelay {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.sleeps_us.push(ns / 1000);
+        }
+
+        async fn delay_us(&mut self, us: u32) {
+            self.sleeps_us.push(us);
+        }
+    }
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// Poll the future to completion. The mocks never return `Poll::Pending`.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Arc::new(NoopWaker).into();
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn read_words_with_crc() {
+        let mut buf = [0; 3];
+
+        // Valid CRC
+        {
+            let expectations = [Transaction::read(0x58, vec![0xBE, 0xEF, 0x92])];
+            let mut mock = AsyncI2cMock(I2cMock::new(&expectations));
+            block_on(i2c_async::read_words_with_crc(&mut mock, 0x58, &mut buf)).unwrap();
+            assert_eq!(buf, [0xbe, 0xef, 0x92]);
+            mock.0.done();
+        }
+
+        // Invalid CRC
+        {
+            let expectations = [Transaction::read(0x58, vec![0xBE, 0xEF, 0x00])];
+            let mut mock = AsyncI2cMock(I2cMock::new(&expectations));
+            match block_on(i2c_async::read_words_with_crc(&mut mock, 0x58, &mut buf)) {
+                Err(i2c_async::Error::Crc) => {}
+                Err(_) => panic!("Invalid error: Must be Crc"),
+                Ok(_) => panic!("CRC check did not fail"),
+            }
+            mock.0.done();
+        }
+    }
+
+    #[test]
+    fn read_words_with_crc_empty() {
+        let mut mock = AsyncI2cMock(I2cMock::new(&[]));
+        block_on(i2c_async::read_words_with_crc(&mut mock, 0x58, &mut [])).unwrap();
+        mock.0.done();
+    }
+
+    #[test]
+    fn read_words_with_crc_retry() {
+        let mut buf = [0; 3];
+        let policy = i2c_async::RetryPolicy {
+            max_attempts: 3,
+            delay_us: 100,
+            backoff: i2c_async::Backoff::Exponential,
+        };
+
+        let expectations = [
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x00]).with_error(ErrorKind::Other),
+            Transaction::read(0x58, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = AsyncI2cMock(I2cMock::new(&expectations));
+        let mut delay = RecordingDelay::default();
+        block_on(i2c_async::read_words_with_crc_retry(
+            &mut mock, 0x58, &mut buf, &mut delay, &policy,
+        ))
+        .unwrap();
+        assert_eq!(buf, [0xbe, 0xef, 0x92]);
+        assert_eq!(delay.sleeps_us, [100, 200]);
+        mock.0.done();
+    }
+
+    #[test]
+    fn measure() {
+        let mut buf = [0; 3];
+
+        let expectations = [
+            Transaction::write(0x70, vec![0x78, 0x66]),
+            Transaction::read(0x70, vec![0xbe, 0xef, 0x92]),
+        ];
+        let mut mock = AsyncI2cMock(I2cMock::new(&expectations));
+        let mut delay = RecordingDelay::default();
+        block_on(i2c_async::measure(
+            &mut mock, 0x70, 0x7866, &mut delay, 12_100, &mut buf,
+        ))
+        .unwrap();
+        assert_eq!(buf, [0xbe, 0xef, 0x92]);
+        assert_eq!(delay.sleeps_us, [12_100]);
+        mock.0.done();
+    }
+
+    #[test]
+    fn write_command_u8() {
+        let expectations = [Transaction::write(0x58, vec![0xab])];
+        let mut mock = AsyncI2cMock(I2cMock::new(&expectations));
+
+        block_on(i2c_async::write_command_u8(&mut mock, 0x58, 0xab)).unwrap();
+
+        mock.0.done();
+    }
+
+    #[test]
+    fn write_command_u16() {
+        let expectations = [Transaction::write(0x58, vec![0xab, 0xcd])];
+        let mut mock = AsyncI2cMock(I2cMock::new(&expectations));
+
+        block_on(i2c_async::write_command_u16(&mut mock, 0x58, 0xabcd)).unwrap();
+
+        mock.0.done();
+    }
+
+    #[test]
+    fn write_command_u16_crc() {
+        let expectations = [Transaction::write(0x58, vec![0xbe, 0xef, 0x92])];
+        let mut mock = AsyncI2cMock(I2cMock::new(&expectations));
+
+        block_on(i2c_async::write_command_u16_crc(&mut mock, 0x58, 0xbeef)).unwrap();
+
+        mock.0.done();
+    }
+
+    #[test]
+    fn read_words_with_ready_bit() {
+        let mut buf = [0; 6];
+
+        let expectations = [
+            Transaction::read(0x58, vec![0x80, 0x00, 0xa2, 0x12, 0x34, 0x37]),
+            Transaction::read(0x58, vec![0x00, 0x00, 0x81, 0x12, 0x34, 0x37]),
+        ];
+        let mut mock = AsyncI2cMock(I2cMock::new(&expectations));
+        assert!(block_on(i2c_async::read_words_with_ready_bit(
+            &mut mock, 0x58, &mut buf, 0x8000
+        ))
+        .unwrap());
+        assert!(!block_on(i2c_async::read_words_with_ready_bit(
+            &mut mock, 0x58, &mut buf, 0x8000
+        ))
+        .unwrap());
+        mock.0.done();
+    }
+
+    #[test]
+    fn poll_until_ready() {
+        let policy = i2c_async::RetryPolicy {
+            max_attempts: 3,
+            delay_us: 100,
+            backoff: i2c_async::Backoff::Exponential,
+        };
+
+        let expectations = [
+            Transaction::write(0x62, vec![0xe4, 0xb8]),
+            Transaction::read(0x62, vec![0x80, 0x00, 0xa2]),
+            Transaction::write(0x62, vec![0xe4, 0xb8]),
+            Transaction::read(0x62, vec![0x00, 0x00, 0x81]),
+            Transaction::write(0x62, vec![0xe4, 0xb8]),
+            Transaction::read(0x62, vec![0x00, 0x01, 0xb0]),
+        ];
+        let mut mock = AsyncI2cMock(I2cMock::new(&expectations));
+        let mut delay = RecordingDelay::default();
+        let ready = block_on(i2c_async::poll_until_ready(
+            &mut mock, 0x62, 0xe4b8, 0x07ff, &mut delay, &policy,
+        ))
+        .unwrap();
+        assert!(ready);
+        assert_eq!(delay.sleeps_us, [100, 200]);
+        mock.0.done();
+    }
 }
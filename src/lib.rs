@@ -15,11 +15,18 @@
 //! assert_eq!(Ok(()), crc8::validate(&data));
 //! ```
 //!
+//! With the `std` Cargo feature enabled, `crc8::ValidatingReader` validates and decodes words
+//! from any `std::io::Read` source, e.g. a captured bus log, and `i2c::diff_words` reports the
+//! mismatching words of a read for verification tests.
+//!
 //! ### I2C
 //!
-//! The I2C helpers work with any `embedded_hal::i2c` implementation.
+//! The I2C helpers work with any `embedded_hal::i2c` implementation. They are enabled by the
+//! default `i2c` Cargo feature. Building with `default-features = false` leaves only the `crc8`
+//! module and drops the `embedded-hal` dependency.
 //!
 //! ```
+//! # #[cfg(feature = "i2c")] {
 //! use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
 //! use sensirion_i2c::i2c;
 //!
@@ -27,6 +34,7 @@
 //! let mut i2c_mock = I2cMock::new(&expectations);
 //! i2c::write_command_u16(&mut i2c_mock, 0x12, 0x3456);
 //! i2c_mock.done();
+//! # }
 //! ```
 //!
 //! #### `embedded-hal-async`
@@ -40,10 +48,21 @@
 //! Cargo feature is enabled.
 
 #![deny(unsafe_code)]
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 pub mod crc8;
+#[cfg(feature = "i2c")]
 pub mod i2c;
 #[cfg(feature = "embedded-hal-async")]
 pub mod i2c_async;
+#[cfg(test)]
+mod test_util;
+
+/// Re-exports used by the macros of this crate. Not part of the public API.
+#[cfg(feature = "i2c")]
+#[doc(hidden)]
+pub mod __private {
+    pub use embedded_hal::delay::DelayNs;
+    pub use embedded_hal::i2c::I2c;
+}
@@ -0,0 +1,26 @@
+//! Helpers shared by the unit tests of several modules
+
+/// Fixed size `core::fmt::Write` target, like it would be used on `no_std`.
+#[derive(Default)]
+pub(crate) struct FixedBuf {
+    data: [u8; 32],
+    len: usize,
+}
+
+impl FixedBuf {
+    pub(crate) fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.data[..self.len]).unwrap()
+    }
+}
+
+impl core::fmt::Write for FixedBuf {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        if end > self.data.len() {
+            return Err(core::fmt::Error);
+        }
+        self.data[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+//! Uses only the `crc8` API, so that it also builds with `--no-default-features`.
+
+use sensirion_i2c::crc8;
+
+#[test]
+fn crc8_without_i2c() {
+    let crc = crc8::calculate(&[0xbe, 0xef]);
+    assert_eq!(crc, 0x92);
+    assert_eq!(crc8::validate(&[0xbe, 0xef, crc]), Ok(()));
+}